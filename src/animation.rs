@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use image::AnimationDecoder;
+use image::codecs::gif::GifDecoder;
+
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    frames: Vec<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>,
+    fps: f32,
+    looping: bool,
+}
+
+impl AnimationClip {
+    pub fn new(frames: Vec<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>, fps: f32, looping: bool) -> Self {
+        assert!(!frames.is_empty(), "Animation clip needs at least one frame");
+
+        Self {
+            frames,
+            fps,
+            looping,
+        }
+    }
+
+    pub fn single(frame: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>) -> Self {
+        Self::new(vec![frame], 0.0, false)
+    }
+
+    pub fn from_gif(gif_bytes: &[u8], fps: f32, looping: bool) -> Self {
+        let decoder = GifDecoder::new(Cursor::new(gif_bytes))
+            .expect("Failed to decode animation gif");
+        let frames = decoder.into_frames()
+            .collect_frames()
+            .expect("Failed to load animation frames")
+            .into_iter()
+            .map(|frame| frame.into_buffer())
+            .collect();
+
+        Self::new(frames, fps, looping)
+    }
+
+    pub fn frames(&self) -> &[image::ImageBuffer<image::Rgba<u8>, Vec<u8>>] {
+        &self.frames
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Animator {
+    clips: HashMap<String, AnimationClip>,
+    current: String,
+    frame: usize,
+    timer: f32,
+    finished: bool,
+    paused: bool,
+}
+
+impl Animator {
+    pub fn new(name: &str, clip: AnimationClip) -> Self {
+        let mut clips = HashMap::new();
+        clips.insert(name.to_string(), clip);
+
+        Self {
+            clips,
+            current: name.to_string(),
+            frame: 0,
+            timer: 0.0,
+            finished: false,
+            paused: false,
+        }
+    }
+
+    pub fn with_clip(mut self, name: &str, clip: AnimationClip) -> Self {
+        self.clips.insert(name.to_string(), clip);
+        self
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Switches to `name` from its first frame; playing the current clip again is a no-op.
+    pub fn play(&mut self, name: &str) {
+        if self.current == name || !self.clips.contains_key(name) {
+            return;
+        }
+
+        self.restart(name);
+    }
+
+    pub fn restart(&mut self, name: &str) {
+        if !self.clips.contains_key(name) {
+            return;
+        }
+
+        self.current = name.to_string();
+        self.frame = 0;
+        self.timer = 0.0;
+        self.finished = false;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let clip = &self.clips[&self.current];
+
+        if self.paused || self.finished {
+            return;
+        }
+
+        if clip.fps <= 0.0 {
+            self.finished = !clip.looping;
+            return;
+        }
+
+        self.timer += dt;
+        let frame_time = 1.0 / clip.fps;
+
+        while self.timer >= frame_time {
+            self.timer -= frame_time;
+
+            if self.frame + 1 < clip.frames.len() {
+                self.frame += 1;
+            } else if clip.looping {
+                self.frame = 0;
+            } else {
+                self.finished = true;
+                break;
+            }
+        }
+    }
+
+    pub fn frame(&self) -> &image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+        &self.clips[&self.current].frames[self.frame]
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::animation::AnimationClip;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct FrameRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClipDescriptor {
+    pub frames: Vec<String>,
+    pub fps: f32,
+    #[serde(default = "default_looping")]
+    pub looping: bool,
+}
+
+fn default_looping() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AtlasDescriptor {
+    pub frames: HashMap<String, FrameRect>,
+    #[serde(default)]
+    pub clips: HashMap<String, ClipDescriptor>,
+}
+
+/// Named frames sliced out of a single sprite sheet.
+#[derive(Debug, Clone)]
+pub struct Atlas {
+    frames: HashMap<String, image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>,
+    clips: HashMap<String, ClipDescriptor>,
+}
+
+impl Atlas {
+    pub fn from_bytes(sheet_bytes: &[u8], descriptor: &str) -> Result<Self, String> {
+        let descriptor: AtlasDescriptor = serde_json::from_str(descriptor)
+            .map_err(|e| format!("Invalid atlas descriptor: {}", e))?;
+        let sheet = image::load_from_memory(sheet_bytes)
+            .map_err(|e| format!("Failed to load sprite sheet: {}", e))?
+            .to_rgba8();
+
+        let mut frames = HashMap::new();
+        for (name, rect) in descriptor.frames {
+            if rect.x + rect.w > sheet.width() || rect.y + rect.h > sheet.height() {
+                return Err(format!("Frame '{}' lies outside the {}x{} sheet", name, sheet.width(), sheet.height()));
+            }
+
+            let frame = image::imageops::crop_imm(&sheet, rect.x, rect.y, rect.w, rect.h).to_image();
+            frames.insert(name, frame);
+        }
+
+        for (name, clip) in &descriptor.clips {
+            if let Some(missing) = clip.frames.iter().find(|frame| !frames.contains_key(*frame)) {
+                return Err(format!("Clip '{}' references unknown frame '{}'", name, missing));
+            }
+        }
+
+        Ok(Self {
+            frames,
+            clips: descriptor.clips,
+        })
+    }
+
+    pub fn frame(&self, name: &str) -> Option<&image::ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+        self.frames.get(name)
+    }
+
+    /// Returns every named frame, or `None` if any of them is missing.
+    pub fn frames(&self, names: &[String]) -> Option<Vec<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>> {
+        names.iter().map(|name| self.frame(name).cloned()).collect()
+    }
+
+    pub fn clip(&self, name: &str) -> Option<AnimationClip> {
+        let clip = self.clips.get(name)?;
+        let frames = self.frames(&clip.frames)?;
+
+        Some(AnimationClip::new(frames, clip.fps, clip.looping))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shipped() -> Atlas {
+        Atlas::from_bytes(include_bytes!("../assets/atlas.png"), include_str!("../assets/atlas.json"))
+            .expect("Failed to load sprite atlas")
+    }
+
+    #[test]
+    fn shipped_atlas_has_every_looked_up_frame() {
+        let atlas = shipped();
+
+        let digits: Vec<String> = (0..10).map(|idx| format!("digit_{}", idx)).collect();
+        assert!(atlas.frames(&digits).is_some());
+        assert!(atlas.frame("toppipe").is_some());
+        assert!(atlas.frame("bottompipe").is_some());
+
+        for state in ["idle", "flap", "glide", "dead"] {
+            assert!(atlas.clip(&format!("bird_{}", state)).is_some(), "missing bird_{}", state);
+        }
+    }
+
+    #[test]
+    fn rejects_frames_outside_the_sheet() {
+        let descriptor = r#"{ "frames": { "huge": { "x": 0, "y": 0, "w": 10000, "h": 1 } } }"#;
+        assert!(Atlas::from_bytes(include_bytes!("../assets/atlas.png"), descriptor).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::daily;
+use crate::physics::Physics;
+use crate::records::{Records, RunLog, RunStats};
+use crate::storage::Storage;
+
+/// Where stats are served unless `--address` says otherwise.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7879";
+
+/// Settings for `orange background`.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub database: String,
+    pub address: String,
+    /// Time between maintenance passes.
+    pub interval: Duration,
+    /// Runs older than this many days are folded into daily summaries.
+    pub keep_days: i64,
+    /// Daily courses worked out ahead of time, counting today.
+    pub days_ahead: i64,
+}
+
+impl Config {
+    pub const USAGE: &'static str = "Usage: orange background [--db path] [--address host:port] [--interval secs] [--keep-days n] [--days-ahead n]";
+
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut config = Self {
+            database: Storage::PATH.to_string(),
+            address: DEFAULT_ADDRESS.to_string(),
+            interval: Duration::from_secs(3600),
+            keep_days: 30,
+            days_ahead: 7,
+        };
+
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let value = args.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+            let invalid = |e: &dyn std::fmt::Display| format!("Invalid value for {}: {}", flag, e);
+
+            match flag.as_str() {
+                "--db" => config.database = value.clone(),
+                "--address" => config.address = value.clone(),
+                "--interval" => config.interval = Duration::from_secs(value.parse().map_err(|e| invalid(&e))?),
+                "--keep-days" => config.keep_days = value.parse().map_err(|e| invalid(&e))?,
+                "--days-ahead" => config.days_ahead = value.parse().map_err(|e| invalid(&e))?,
+                _ => return Err(format!("Unknown flag {}", flag)),
+            }
+        }
+
+        if config.interval.is_zero() {
+            return Err("--interval must be at least 1".to_string());
+        }
+
+        if config.keep_days < 1 || config.days_ahead < 1 {
+            return Err("--keep-days and --days-ahead must be at least 1".to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+/// Seeds of the upcoming daily courses by date, kept in `cache.db` for anything that wants to look ahead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailySchedule {
+    pub seeds: BTreeMap<String, u64>,
+}
+
+impl DailySchedule {
+    const KEY: &'static str = "FlappyDailySchedule";
+
+    pub fn load(storage: &Storage) -> Self {
+        storage.get(Self::KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &Storage) {
+        storage.set(Self::KEY, self);
+    }
+
+    /// `days` daily courses starting with `today`.
+    pub fn upcoming(today: i64, days: i64) -> Self {
+        Self {
+            seeds: (today..today + days).map(|day| (daily::date_string(day), daily::seed(day))).collect(),
+        }
+    }
+}
+
+/// What the stats socket sends each client, one JSON line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Status {
+    pub runs: RunStats,
+    pub daily_best: usize,
+    pub daily_streak: u32,
+    pub upcoming: BTreeMap<String, u64>,
+    /// Seconds since the epoch of the last maintenance pass.
+    pub maintained_at: Option<u64>,
+}
+
+/// Keeps `cache.db` tidy on a timer and answers every connection to `config.address` with a `Status`.
+pub fn run(config: &Config) -> Result<(), String> {
+    let listener = TcpListener::bind(&config.address).map_err(|e| format!("Failed to bind {}: {}", config.address, e))?;
+    let maintained_at = Arc::new(Mutex::new(None));
+    println!("> Serving stats for {} on {}", config.database, config.address);
+
+    let database = config.database.clone();
+    let served_at = Arc::clone(&maintained_at);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let result = stream
+                .map_err(|e| format!("Failed to accept: {}", e))
+                .and_then(|stream| serve(stream, &database, *served_at.lock().expect("Failed to lock status")));
+
+            if let Err(e) = result {
+                println!("> {}", e);
+            }
+        }
+    });
+
+    loop {
+        maintain(config);
+        *maintained_at.lock().expect("Failed to lock status") = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|elapsed| elapsed.as_secs());
+
+        std::thread::sleep(config.interval);
+    }
+}
+
+/// One maintenance pass: folds old runs into summaries and works out the coming daily courses.
+pub fn maintain(config: &Config) {
+    let Some(today) = daily::today() else {
+        return;
+    };
+
+    let storage = Storage::open_at(&config.database);
+
+    if let Some(folded @ 1..) = RunLog::compact_stored(&storage, today - config.keep_days) {
+        println!("> Compacted {} runs older than {} days", folded, config.keep_days);
+    }
+
+    DailySchedule::upcoming(today, config.days_ahead).save(&storage);
+}
+
+fn serve(mut stream: TcpStream, database: &str, maintained_at: Option<u64>) -> Result<(), String> {
+    let storage = Storage::open_at(database);
+    let today = daily::today().unwrap_or(0);
+    let records = Records::load(&storage);
+
+    let status = Status {
+        runs: RunLog::load(&storage).stats(today, Physics::default().tick),
+        daily_best: records.daily_best(today),
+        daily_streak: records.daily_streak(today),
+        upcoming: DailySchedule::load(&storage).seeds,
+        maintained_at,
+    };
+
+    let mut line = serde_json::to_vec(&status).expect("Failed to serialize status");
+    line.push(b'\n');
+    stream.write_all(&line).map_err(|e| format!("Failed to send status: {}", e))
+}
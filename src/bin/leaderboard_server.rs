@@ -0,0 +1,148 @@
+//! A small leaderboard server speaking the game's JSON line protocol, for testing the whole
+//! submit-and-rank flow offline. Every run is re-simulated from its replay before it is ranked.
+//!
+//! cargo run --bin leaderboard_server -- --address 127.0.0.1:7878 --data leaderboard.json
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use serde::{Serialize, Deserialize};
+
+use main::leaderboard::{Entry, Request, Response, Submission, DEFAULT_ADDRESS};
+use main::verify;
+
+/// Each player's best run on each board.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Boards {
+    boards: BTreeMap<String, Vec<Submission>>,
+}
+
+impl Boards {
+    fn load(path: &str) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                println!("> Failed to read {}, starting empty: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn save(&self, path: &str) {
+        let json = serde_json::to_string(self).expect("Failed to serialize leaderboard");
+        if let Err(e) = std::fs::write(path, json) {
+            println!("> Failed to write {}: {}", path, e);
+        }
+    }
+
+    fn submit(&mut self, submission: Submission) -> Response {
+        let board = self.boards.entry(submission.board.clone()).or_default();
+        let player = submission.player.clone();
+
+        match board.iter_mut().find(|best| best.player == player) {
+            Some(best) if best.score >= submission.score => {}
+            Some(best) => *best = submission,
+            None => board.push(submission),
+        }
+        // Stable, so equal scores keep whoever got there first.
+        board.sort_by_key(|best| Reverse(best.score));
+
+        let rank = board.iter().position(|best| best.player == player).map_or(0, |idx| idx + 1);
+        Response::Submitted { rank, total: board.len() }
+    }
+
+    fn top(&self, board: &str, limit: usize, player: &str) -> Response {
+        let entries: Vec<Entry> = self.boards.get(board).map(|runs| {
+            runs.iter().enumerate().map(|(idx, run)| Entry {
+                rank: idx + 1,
+                player: run.player.clone(),
+                score: run.score,
+            }).collect()
+        }).unwrap_or_default();
+
+        Response::Top {
+            player: entries.iter().find(|entry| entry.player == player).cloned(),
+            total: entries.len(),
+            entries: entries.into_iter().take(limit).collect(),
+        }
+    }
+}
+
+fn handle(stream: TcpStream, boards: &Mutex<Boards>, data_path: &str) -> Result<(), String> {
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| format!("Failed to clone stream: {}", e))?);
+    let mut writer = stream;
+
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| format!("Failed to read request: {}", e))?;
+
+    let response = match serde_json::from_str::<Request>(&line) {
+        Ok(Request::Submit { submission }) => {
+            println!("> {} scored {} on {}", submission.player, submission.score, submission.board);
+
+            // Re-simulating can take a while, so it runs before the boards are locked.
+            match verify::verify(&submission) {
+                Ok(()) => {
+                    let mut boards = boards.lock().expect("Failed to lock leaderboard");
+                    let response = boards.submit(submission);
+                    boards.save(data_path);
+                    response
+                }
+                Err(reason) => {
+                    println!("> Rejected: {}", reason);
+                    Response::Rejected { reason }
+                }
+            }
+        }
+        Ok(Request::Top { board, limit, player }) => {
+            boards.lock().expect("Failed to lock leaderboard").top(&board, limit, &player)
+        }
+        Err(e) => Response::Rejected { reason: format!("Invalid request: {}", e) },
+    };
+
+    let mut reply = serde_json::to_vec(&response).expect("Failed to serialize response");
+    reply.push(b'\n');
+    writer.write_all(&reply).map_err(|e| format!("Failed to send reply: {}", e))
+}
+
+fn main() {
+    let mut address = DEFAULT_ADDRESS.to_string();
+    let mut data_path = "leaderboard.json".to_string();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        match (flag.as_str(), args.next()) {
+            ("--address", Some(value)) => address = value,
+            ("--data", Some(value)) => data_path = value,
+            _ => {
+                println!("> Usage: leaderboard_server [--address host:port] [--data path]");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let listener = TcpListener::bind(&address).expect("Failed to bind leaderboard address");
+    let boards = Arc::new(Mutex::new(Boards::load(&data_path)));
+    let data_path = Arc::new(data_path);
+    println!("> Leaderboard listening on {}", address);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("> Failed to accept: {}", e);
+                continue;
+            }
+        };
+
+        let boards = Arc::clone(&boards);
+        let data_path = Arc::clone(&data_path);
+        std::thread::spawn(move || {
+            if let Err(e) = handle(stream, &boards, &data_path) {
+                println!("> {}", e);
+            }
+        });
+    }
+}
@@ -0,0 +1,335 @@
+//! Plays two scripted peers against each other through a loopback proxy that delays and drops
+//! their messages. Each side runs the whole game with rollback, and both must end up agreeing on
+//! both birds, the scores and how the round went.
+//!
+//! cargo run --bin rollback_harness -- --delay 80 --jitter 30 --loss 0.1 --ticks 600
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use rand::Rng;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use main::mode::ModeKind;
+use main::net::{Message, START_Y};
+use main::rollback::{BirdFrame, InputLog, Rollback};
+use main::sim::{Ending, FlyerSnapshot, Simulation};
+
+const TICK: Duration = Duration::from_millis(16);
+/// Ticks both peers keep repeating their last inputs after the run, so they get through the loss.
+const SETTLE_TICKS: u64 = 60;
+/// Course both peers play, as the host would have sent it.
+const SEED: u64 = 1;
+
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    delay_ms: u64,
+    jitter_ms: u64,
+    loss: f64,
+    ticks: u64,
+}
+
+impl Config {
+    fn from_args() -> Result<Self, String> {
+        let mut config = Self {
+            delay_ms: 80,
+            jitter_ms: 20,
+            loss: 0.05,
+            ticks: 600,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(flag) = args.next() {
+            let value = args.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+            let invalid = |e: &dyn std::fmt::Display| format!("Invalid value for {}: {}", flag, e);
+
+            match flag.as_str() {
+                "--delay" => config.delay_ms = value.parse().map_err(|e| invalid(&e))?,
+                "--jitter" => config.jitter_ms = value.parse().map_err(|e| invalid(&e))?,
+                "--loss" => config.loss = value.parse().map_err(|e| invalid(&e))?,
+                "--ticks" => config.ticks = value.parse().map_err(|e| invalid(&e))?,
+                _ => return Err(format!("Unknown flag {}", flag)),
+            }
+        }
+
+        if config.ticks == 0 {
+            return Err("--ticks must be at least 1".to_string());
+        }
+
+        if !(0.0..1.0).contains(&config.loss) {
+            return Err("--loss must be at least 0 and below 1".to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+/// What one peer saw over the run.
+struct PeerLog {
+    /// Own bird at the start of every played tick.
+    flown: Vec<BirdFrame>,
+    /// The opponent as drawn at each played tick, before any later correction.
+    shown: Vec<BirdFrame>,
+    /// Own bird and the opponent's as this side ended up with them.
+    player: FlyerSnapshot,
+    rival: FlyerSnapshot,
+    ticks: u64,
+    ending: Option<Ending>,
+    rollback: Rollback,
+    max_predicted: u64,
+}
+
+/// Flaps to fly through the next gap, weaving a little either side of its middle.
+fn bot_flaps(sim: &Simulation, tick: u64, phase: f32, cooldown: &mut u32) -> bool {
+    if *cooldown > 0 {
+        *cooldown -= 1;
+        return false;
+    }
+
+    let pipes = sim.pipes();
+    let gap_y = pipes.pairs.iter()
+        .find(|pair| pair.x + pipes.pipe_width > sim.bird_x())
+        .map_or(START_Y, |pair| pipes.resolve_gap(pair).0);
+    let target = gap_y + 30.0 * (tick as f32 * 0.05 + phase).sin();
+
+    let bird = sim.bird();
+    if bird.y + sim.bird_size().1 / 2.0 > target && bird.momentum >= 0.0 {
+        *cooldown = 6;
+        return true;
+    }
+
+    false
+}
+
+async fn run_peer(stream: TcpStream, phase: f32, config: Config) -> PeerLog {
+    let (reader, mut writer) = stream.into_split();
+    let (incoming_tx, incoming) = mpsc::channel();
+    let (outgoing, mut outgoing_rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
+
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Ok(message) = serde_json::from_str::<Message>(&line) {
+                if incoming_tx.send(message).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        while let Some(message) = outgoing_rx.recv().await {
+            let mut line = serde_json::to_vec(&message).expect("Failed to serialize message");
+            line.push(b'\n');
+            if writer.write_all(&line).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    let mode = ModeKind::Versus;
+    let mut sim = Simulation::new(mode, mode.build().generator(), SEED);
+    sim.add_rival(START_Y);
+
+    let mut rollback = Rollback::new();
+    let mut inputs = InputLog::new();
+    let mut next_unsent = 0;
+    let mut cooldown = 0;
+    let mut flown = Vec::new();
+    let mut shown = Vec::new();
+    let mut ending = None;
+    let mut max_predicted = 0;
+    let mut settled = 0;
+
+    let mut interval = tokio::time::interval(TICK);
+    while settled < SETTLE_TICKS {
+        interval.tick().await;
+
+        for message in incoming.try_iter() {
+            if let Message::Inputs { up_to, flaps, .. } = message {
+                rollback.receive(up_to, &flaps);
+            }
+        }
+
+        let tick = rollback.tick();
+        let playing = ending.is_none() && tick < config.ticks;
+
+        // A tick held while the round's end is confirmed keeps the input it first went out with.
+        if playing && tick >= next_unsent {
+            let flapped = bot_flaps(&sim, tick, phase, &mut cooldown);
+            rollback.record_local(flapped);
+            if flapped {
+                inputs.record(tick);
+            }
+            next_unsent = tick + 1;
+        }
+
+        if let Some(up_to) = next_unsent.checked_sub(1) {
+            let _ = outgoing.send(Message::Inputs { round: 1, up_to, flaps: inputs.recent(up_to) });
+        }
+
+        rollback.catch_up(&mut sim);
+        if !playing {
+            settled += 1;
+            continue;
+        }
+
+        let (own, opponent) = (sim.bird(), sim.rival.as_ref().map(|rival| rival.bird));
+        max_predicted = max_predicted.max(rollback.predicted_ticks());
+
+        match rollback.advance(&mut sim) {
+            Ok(Some(_)) => {
+                flown.push(own);
+                shown.push(opponent.expect("Versus run has no rival"));
+            }
+            Ok(None) => {}
+            Err(end) => ending = Some(end),
+        }
+    }
+
+    PeerLog {
+        flown,
+        shown,
+        player: sim.player.snapshot(),
+        rival: sim.rival.as_ref().expect("Versus run has no rival").snapshot(),
+        ticks: rollback.tick(),
+        ending,
+        rollback,
+        max_predicted,
+    }
+}
+
+/// Forwards lines from `reader` to `writer`, dropping some and holding the rest back a while.
+async fn forward(reader: tokio::net::tcp::OwnedReadHalf, mut writer: tokio::net::tcp::OwnedWriteHalf, config: Config) {
+    let (delayed, mut delayed_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+
+    tokio::spawn(async move {
+        while let Some(line) = delayed_rx.recv().await {
+            if writer.write_all(format!("{}\n", line).as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let (dropped, delay) = {
+            let mut rng = rand::rng();
+            let jitter = if config.jitter_ms > 0 { rng.random_range(0..=config.jitter_ms * 2) } else { 0 };
+            (rng.random_range(0.0..1.0) < config.loss, (config.delay_ms + jitter).saturating_sub(config.jitter_ms))
+        };
+
+        if dropped {
+            continue;
+        }
+
+        // Each line sleeps on its own, so jitter can reorder them like datagrams.
+        let delayed = delayed.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            let _ = delayed.send(line);
+        });
+    }
+}
+
+async fn run_proxy(listener: TcpListener, upstream: std::net::SocketAddr, config: Config) -> Result<(), String> {
+    let (client, _) = listener.accept().await.map_err(|e| format!("Failed to accept: {}", e))?;
+    let server = TcpStream::connect(upstream).await.map_err(|e| format!("Failed to reach peer: {}", e))?;
+
+    let (client_reader, client_writer) = client.into_split();
+    let (server_reader, server_writer) = server.into_split();
+
+    tokio::spawn(forward(client_reader, server_writer, config));
+    tokio::spawn(forward(server_reader, client_writer, config));
+    Ok(())
+}
+
+/// Compares `seen`'s final view of the opponent with what the opponent ended up with themselves.
+fn report(name: &str, seen: &PeerLog, truth: &PeerLog) -> bool {
+    let max_shown_error = seen.shown.iter().zip(&truth.flown)
+        .map(|(shown, flown)| (shown.y - flown.y).abs())
+        .fold(0.0, f32::max);
+
+    let as_json = |flyer: &FlyerSnapshot| serde_json::to_value(flyer).expect("Failed to serialize bird");
+    let converged = seen.ticks == truth.ticks
+        && seen.ending == truth.ending
+        && as_json(&seen.rival) == as_json(&truth.player);
+
+    println!(
+        "> {}: {} rollbacks, {} ticks re-simulated, up to {} ticks predicted, {} flaps too late, worst shown error {:.1}px, converged: {}",
+        name,
+        seen.rollback.rollbacks,
+        seen.rollback.resimulated,
+        seen.max_predicted,
+        seen.rollback.too_late,
+        max_shown_error,
+        if converged { "yes" } else { "NO" },
+    );
+
+    converged
+}
+
+fn main() {
+    let config = match Config::from_args() {
+        Ok(config) => config,
+        Err(e) => {
+            println!("> {}", e);
+            println!("> Usage: rollback_harness [--delay ms] [--jitter ms] [--loss 0..1] [--ticks n]");
+            std::process::exit(2);
+        }
+    };
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to start tokio runtime");
+
+    let result: Result<(PeerLog, PeerLog), String> = runtime.block_on(async move {
+        let host = TcpListener::bind("127.0.0.1:0").await.map_err(|e| format!("Failed to listen: {}", e))?;
+        let host_address = host.local_addr().map_err(|e| format!("Failed to read address: {}", e))?;
+        let proxy = TcpListener::bind("127.0.0.1:0").await.map_err(|e| format!("Failed to listen: {}", e))?;
+        let proxy_address = proxy.local_addr().map_err(|e| format!("Failed to read address: {}", e))?;
+
+        let proxy = tokio::spawn(run_proxy(proxy, host_address, config));
+
+        // Both peers stay on this thread: the game isn't `Send`, just as it lives on one thread in the app.
+        let peers = tokio::task::LocalSet::new();
+        peers.run_until(async move {
+            let joiner = tokio::task::spawn_local(async move {
+                let stream = TcpStream::connect(proxy_address).await.map_err(|e| format!("Failed to reach proxy: {}", e))?;
+                Ok::<_, String>(run_peer(stream, 1.7, config).await)
+            });
+
+            let (stream, _) = host.accept().await.map_err(|e| format!("Failed to accept: {}", e))?;
+            proxy.await.map_err(|e| format!("Proxy failed: {}", e))??;
+
+            let host_log = run_peer(stream, 0.0, config).await;
+            let join_log = joiner.await.map_err(|e| format!("Peer failed: {}", e))??;
+            Ok((host_log, join_log))
+        }).await
+    });
+
+    let (host_log, join_log) = match result {
+        Ok(logs) => logs,
+        Err(e) => {
+            println!("> {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    println!(
+        "> {} ticks over loopback, {}±{} ms delay, {:.0}% loss",
+        config.ticks, config.delay_ms, config.jitter_ms, config.loss * 100.0,
+    );
+    match host_log.ending {
+        Some(ending) => println!("> Round ended ({}) at tick {}", ending.name(), host_log.ticks),
+        None => println!("> Both birds flew all {} ticks", host_log.ticks),
+    }
+    let host_ok = report("Host seen by joiner", &join_log, &host_log);
+    let join_ok = report("Joiner seen by host", &host_log, &join_log);
+
+    if !(host_ok && join_ok) {
+        std::process::exit(1);
+    }
+}
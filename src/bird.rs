@@ -0,0 +1,250 @@
+use prism::Context;
+use prism::canvas::{Image, ShapeType};
+use stork::{Canvas, GameObject};
+
+use crate::animation::Animator;
+use crate::sim::BIRD_START;
+
+/// Drop in vertical momentum between ticks that counts as a flap.
+const FLAP_THRESHOLD: f32 = 5.0;
+/// Falling faster than this switches from idle to glide.
+const GLIDE_VELOCITY: f32 = 1.0;
+/// Off screen, where a bird waits while a menu is up or after it is out of a versus round.
+pub const PARKED: (f32, f32) = (-200.0, 300.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BirdState {
+    Idle,
+    Flap,
+    Glide,
+    Dead,
+}
+
+impl BirdState {
+    pub fn clip_name(&self) -> &'static str {
+        match self {
+            BirdState::Idle => "idle",
+            BirdState::Flap => "flap",
+            BirdState::Glide => "glide",
+            BirdState::Dead => "dead",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TiltConfig {
+    /// Nose-up angle in degrees while rising.
+    pub max_up_angle: f32,
+    /// Nose-down angle in degrees once falling at `dive_velocity`.
+    pub max_down_angle: f32,
+    pub dive_velocity: f32,
+    /// Past this angle the wings stop flapping.
+    pub dive_angle: f32,
+    /// Fraction of the remaining angle covered each tick.
+    pub smoothing: f32,
+}
+
+impl Default for TiltConfig {
+    fn default() -> Self {
+        Self {
+            max_up_angle: 25.0,
+            max_down_angle: 90.0,
+            dive_velocity: 4.0,
+            dive_angle: 45.0,
+            smoothing: 0.2,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Bird {
+    name: String,
+    size: (f32, f32),
+    tags: Vec<String>,
+    animator: Animator,
+    state: BirdState,
+    last_momentum: f32,
+    rotation: f32,
+    scale: f32,
+    tilt: TiltConfig,
+}
+
+impl Bird {
+    pub fn new(
+        name: &str,
+        mut animator: Animator,
+        size: (f32, f32),
+        tags: Vec<String>,
+    ) -> Self {
+        animator.restart(BirdState::Idle.clip_name());
+
+        Self {
+            name: name.to_string(),
+            size,
+            tags,
+            animator,
+            state: BirdState::Idle,
+            last_momentum: 0.0,
+            rotation: 0.0,
+            scale: 1.0,
+            tilt: TiltConfig::default(),
+        }
+    }
+
+    pub fn set_animator(&mut self, mut animator: Animator) {
+        animator.restart(self.state.clip_name());
+        self.animator = animator;
+    }
+
+    pub fn with_tilt(mut self, tilt: TiltConfig) -> Self {
+        self.tilt = tilt;
+        self
+    }
+
+    pub fn set_tilt(&mut self, tilt: TiltConfig) {
+        self.tilt = tilt;
+    }
+
+    /// Scales the sprite and hitbox from the next update on.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.state == BirdState::Dead
+    }
+
+    pub fn is_diving(&self) -> bool {
+        self.rotation >= self.tilt.dive_angle
+    }
+
+    /// Moves the bird off screen and stills it while a menu is up.
+    pub fn park(&self, canvas: &mut Canvas) {
+        self.place(canvas, PARKED);
+    }
+
+    /// Brings a parked bird back to where runs start.
+    pub fn unpark(&self, canvas: &mut Canvas) {
+        self.place(canvas, BIRD_START);
+    }
+
+    fn place(&self, canvas: &mut Canvas, position: (f32, f32)) {
+        if let Some(bird) = canvas.get_game_object_mut(&self.name) {
+            bird.position = position;
+            bird.momentum = (0.0, 0.0);
+        }
+    }
+
+    pub fn spawn(&mut self, ctx: &mut Context, canvas: &mut Canvas, position: (f32, f32)) {
+        self.add_to_canvas(ctx, canvas, position);
+    }
+
+    /// Redraws the bird at `position`, tilted for `vertical_momentum` and animated on by `dt` seconds.
+    ///
+    /// The simulation moves the bird, so the canvas object never moves on its own.
+    pub fn update(&mut self, ctx: &mut Context, canvas: &mut Canvas, position: (f32, f32), vertical_momentum: f32, dt: f32) {
+        let target = self.target_angle(vertical_momentum);
+        self.rotation += (target - self.rotation) * self.tilt.smoothing;
+
+        self.update_state(vertical_momentum);
+        self.last_momentum = vertical_momentum;
+
+        self.animator.set_paused(self.is_diving() && self.state != BirdState::Dead);
+        self.animator.update(dt);
+
+        match canvas.get_game_object_mut(&self.name) {
+            Some(bird) => {
+                let (image, size) = self.sprite();
+                bird.image = image;
+                bird.size = (size, size);
+                bird.position = position;
+            }
+            None => self.add_to_canvas(ctx, canvas, position),
+        }
+    }
+
+    pub fn kill(&mut self) {
+        self.set_state(BirdState::Dead);
+    }
+
+    pub fn reset(&mut self) {
+        self.rotation = 0.0;
+        self.scale = 1.0;
+        self.last_momentum = 0.0;
+        self.set_state(BirdState::Idle);
+    }
+
+    fn update_state(&mut self, vertical_momentum: f32) {
+        if self.state == BirdState::Dead {
+            return;
+        }
+
+        if vertical_momentum < self.last_momentum - FLAP_THRESHOLD {
+            self.set_state(BirdState::Flap);
+            return;
+        }
+
+        if self.state == BirdState::Flap && !self.animator.is_finished() {
+            return;
+        }
+
+        if vertical_momentum > GLIDE_VELOCITY {
+            self.set_state(BirdState::Glide);
+        } else {
+            self.set_state(BirdState::Idle);
+        }
+    }
+
+    fn set_state(&mut self, state: BirdState) {
+        if state == BirdState::Flap {
+            self.animator.restart(state.clip_name());
+        } else {
+            self.animator.play(state.clip_name());
+        }
+
+        self.state = state;
+    }
+
+    fn target_angle(&self, vertical_momentum: f32) -> f32 {
+        if self.state == BirdState::Dead {
+            return self.tilt.max_down_angle;
+        }
+
+        if vertical_momentum < 0.0 {
+            return -self.tilt.max_up_angle;
+        }
+
+        let t = (vertical_momentum / self.tilt.dive_velocity).min(1.0);
+        -self.tilt.max_up_angle + t * (self.tilt.max_down_angle + self.tilt.max_up_angle)
+    }
+
+    /// The current frame, scaled and tilted, and the size of its hitbox.
+    fn sprite(&self) -> (Image, f32) {
+        let size = (self.size.0 * self.scale, self.size.1 * self.scale);
+        let img_obj = Image {
+            shape: ShapeType::Rectangle(0.0, size, self.rotation.to_radians()),
+            image: self.animator.frame().clone().into(),
+            color: None
+        };
+
+        (img_obj, size.0.max(size.1))
+    }
+
+    fn add_to_canvas(&self, ctx: &mut Context, canvas: &mut Canvas, position: (f32, f32)) {
+        let (img_obj, size) = self.sprite();
+
+        let bird = GameObject::new(
+            ctx,
+            self.name.clone(),
+            img_obj,
+            size,
+            position,
+            self.tags.clone(),
+            (0.0, 0.0),
+            (1.0, 1.0),
+            0.0,
+        );
+
+        canvas.add_game_object(self.name.clone(), bird);
+    }
+}
@@ -0,0 +1,102 @@
+use rand::Rng;
+use std::collections::VecDeque;
+
+use crate::rng::SeededRng;
+use crate::sim::{Simulation, BIRD_START};
+
+/// Ticks ahead the bot looks when deciding whether it is about to sink below its aim.
+const LOOKAHEAD_TICKS: u32 = 8;
+/// Fewest ticks between flaps, the solver's `min_flap_interval`.
+const FLAP_INTERVAL: u32 = 6;
+/// Input lag, in ticks, of a bot with no skill at all.
+const MAX_LAG_TICKS: f32 = 10.0;
+/// Share of the gap a bot with no skill may be off by when picking where to fly through it.
+const MAX_AIM_ERROR: f32 = 0.45;
+/// Pixels a bot with no skill may misjudge the bird's height by on any one tick.
+const MAX_READ_ERROR: f32 = 90.0;
+
+/// An autopilot for headless runs that flies at a point in the next gap.
+///
+/// Lower skill means reacting later, aiming less accurately and misjudging the bird's height;
+/// a skill of 1 reacts on the tick and always aims for the middle.
+#[derive(Debug)]
+pub struct Bot {
+    skill: f32,
+    rng: SeededRng,
+    /// Decisions still on their way to the wing.
+    lag: VecDeque<bool>,
+    cooldown: u32,
+    /// Pair the bot is aiming through and its aim, as an offset from the gap centre.
+    aim: Option<(u32, f32)>,
+}
+
+impl Bot {
+    pub fn new(skill: f32, seed: u64) -> Self {
+        let skill = skill.clamp(0.0, 1.0);
+        let lag = ((1.0 - skill) * MAX_LAG_TICKS).round() as usize;
+
+        Self {
+            skill,
+            rng: SeededRng::new(seed),
+            lag: std::iter::repeat_n(false, lag).collect(),
+            cooldown: 0,
+            aim: None,
+        }
+    }
+
+    /// Whether to flap this tick, acting on what the bot saw a few ticks ago.
+    pub fn decide(&mut self, sim: &Simulation) -> bool {
+        let flap = self.wants_flap(sim);
+        self.lag.push_back(flap);
+        self.lag.pop_front().unwrap_or(false)
+    }
+
+    fn wants_flap(&mut self, sim: &Simulation) -> bool {
+        if self.cooldown > 0 {
+            self.cooldown -= 1;
+            return false;
+        }
+
+        let target = self.target(sim);
+        let physics = sim.physics();
+        let bird = sim.bird();
+        let (_, height) = sim.bird_size();
+
+        let (mut y, mut momentum) = (bird.y, bird.momentum);
+        for _ in 0..LOOKAHEAD_TICKS {
+            (y, momentum) = physics.step(y, momentum);
+        }
+
+        let spread = (1.0 - self.skill) * MAX_READ_ERROR;
+        let misread = if spread > 0.0 { self.rng.random_range(-spread..spread) } else { 0.0 };
+
+        if y + misread + height / 2.0 <= target {
+            return false;
+        }
+
+        self.cooldown = FLAP_INTERVAL - 1;
+        true
+    }
+
+    /// Height the bird's middle should be at to fly through the next gap still ahead of it, or
+    /// to hold where it started until one shows up.
+    fn target(&mut self, sim: &Simulation) -> f32 {
+        let pipes = sim.pipes();
+        let Some(pair) = pipes.pairs.iter().find(|pair| pair.x + pipes.pipe_width > sim.bird_x()) else {
+            return BIRD_START.1;
+        };
+        let (center_y, size) = pipes.resolve_gap(pair);
+
+        let offset = match self.aim {
+            Some((id, offset)) if id == pair.id => offset,
+            _ => {
+                let spread = (1.0 - self.skill) * MAX_AIM_ERROR;
+                let offset = if spread > 0.0 { self.rng.random_range(-spread..spread) } else { 0.0 };
+                self.aim = Some((pair.id, offset));
+                offset
+            }
+        };
+
+        center_y + offset * size
+    }
+}
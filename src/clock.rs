@@ -0,0 +1,43 @@
+/// Ticks caught up on at most in one call, so a stall doesn't turn into a burst of play.
+const MAX_CATCH_UP: u32 = 5;
+
+/// Turns however often the app calls in into whole ticks of fixed length, so the game runs at the
+/// same speed on every machine and both sides of an online match tick at the same rate.
+#[derive(Debug)]
+pub struct FixedStep {
+    tick: f32,
+    #[cfg(not(target_arch = "wasm32"))]
+    last: Option<std::time::Instant>,
+    /// Seconds passed that haven't made up a whole tick yet.
+    pending: f32,
+}
+
+impl FixedStep {
+    pub fn new(tick: f32) -> Self {
+        Self {
+            tick,
+            #[cfg(not(target_arch = "wasm32"))]
+            last: None,
+            pending: 0.0,
+        }
+    }
+
+    /// Ticks to play now for the time passed since the last call.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn ticks_due(&mut self) -> u32 {
+        let now = std::time::Instant::now();
+        let elapsed = self.last.map_or(self.tick, |last| now.duration_since(last).as_secs_f32());
+        self.last = Some(now);
+
+        self.pending = (self.pending + elapsed).min(self.tick * MAX_CATCH_UP as f32);
+        let due = (self.pending / self.tick) as u32;
+        self.pending -= due as f32 * self.tick;
+        due
+    }
+
+    /// The browser has no monotonic clock here, so every frame plays one tick as before.
+    #[cfg(target_arch = "wasm32")]
+    pub fn ticks_due(&mut self) -> u32 {
+        1
+    }
+}
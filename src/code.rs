@@ -0,0 +1,185 @@
+use crate::mode::ModeKind;
+use crate::obstacle::GeneratorKind;
+
+/// Bump whenever a change makes an existing code play a different course, so old codes are
+/// turned away instead of quietly playing something else.
+pub const COURSE_VERSION: u8 = 2;
+
+/// Crockford base32, which leaves out I, L, O and U so codes survive being read aloud.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const PAYLOAD_BYTES: usize = 7;
+pub const CODE_LENGTH: usize = (PAYLOAD_BYTES * 8).div_ceil(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeCourse {
+    /// A generated course from a fixed seed.
+    Seeded { generator: GeneratorKind, seed: u32 },
+    /// The daily course for a day, in days since the epoch.
+    Daily { day: u32 },
+}
+
+/// A course and the mode to play it in, which a player can hand to someone else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Challenge {
+    pub mode: ModeKind,
+    pub course: ChallengeCourse,
+}
+
+impl Challenge {
+    /// Byte 1 packs the course kind (2 bits), generator (3 bits) and mode (3 bits).
+    fn payload(&self) -> [u8; PAYLOAD_BYTES] {
+        let (kind, generator, value) = match self.course {
+            ChallengeCourse::Seeded { generator, seed } => (0, generator.index(), seed),
+            ChallengeCourse::Daily { day } => (1, 0, day),
+        };
+
+        let mut payload = [0; PAYLOAD_BYTES];
+        payload[0] = COURSE_VERSION;
+        payload[1] = (kind << 6) | (generator << 3) | self.mode.index();
+        payload[2..6].copy_from_slice(&value.to_le_bytes());
+        payload[6] = checksum(&payload[..6]);
+        payload
+    }
+
+    /// The code as three dash-separated groups of four, e.g. `1A2B-3C4D-5E6F`.
+    pub fn encode(&self) -> String {
+        let payload = self.payload();
+        let mut bits: u64 = 0;
+        for byte in payload {
+            bits = (bits << 8) | byte as u64;
+        }
+        bits <<= CODE_LENGTH * 5 - PAYLOAD_BYTES * 8;
+
+        let symbols: Vec<char> = (0..CODE_LENGTH).rev()
+            .map(|idx| ALPHABET[((bits >> (idx * 5)) & 31) as usize] as char)
+            .collect();
+
+        symbols.chunks(4)
+            .map(|group| group.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Reads a code back, ignoring case and dashes and reading I/L as 1 and O as 0.
+    pub fn parse(code: &str) -> Result<Self, String> {
+        let mut bits: u64 = 0;
+        let mut length = 0;
+
+        for c in code.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+            let c = match c.to_ascii_uppercase() {
+                'O' => '0',
+                'I' | 'L' => '1',
+                c => c,
+            };
+            let value = ALPHABET.iter()
+                .position(|symbol| *symbol as char == c)
+                .ok_or_else(|| format!("'{}' can't appear in a challenge code", c))?;
+
+            bits = (bits << 5) | value as u64;
+            length += 1;
+        }
+
+        if length != CODE_LENGTH {
+            return Err(format!("Challenge codes are {} characters long, got {}", CODE_LENGTH, length));
+        }
+
+        let padding = CODE_LENGTH * 5 - PAYLOAD_BYTES * 8;
+        if bits & ((1 << padding) - 1) != 0 {
+            return Err("Challenge code is mistyped".to_string());
+        }
+        bits >>= padding;
+
+        let mut payload = [0; PAYLOAD_BYTES];
+        for (idx, byte) in payload.iter_mut().rev().enumerate() {
+            *byte = (bits >> (idx * 8)) as u8;
+        }
+
+        if checksum(&payload[..6]) != payload[6] {
+            return Err("Challenge code is mistyped".to_string());
+        }
+
+        if payload[0] != COURSE_VERSION {
+            return Err(format!(
+                "Challenge code is for course version {}, this game plays version {}",
+                payload[0],
+                COURSE_VERSION,
+            ));
+        }
+
+        let mode = ModeKind::from_index(payload[1] & 0x07)
+            .ok_or_else(|| "Challenge code uses a mode this game doesn't have".to_string())?;
+
+        let value = u32::from_le_bytes([payload[2], payload[3], payload[4], payload[5]]);
+        let course = match (payload[1] >> 6, (payload[1] >> 3) & 0x07) {
+            (0, generator) => {
+                let generator = GeneratorKind::from_index(generator)
+                    .ok_or_else(|| "Challenge code uses a course generator this game doesn't have".to_string())?;
+                ChallengeCourse::Seeded { generator, seed: value }
+            }
+            (1, _) => ChallengeCourse::Daily { day: value },
+            _ => return Err("Challenge code is for a kind of course this game doesn't have".to_string()),
+        };
+
+        Ok(Challenge { mode, course })
+    }
+}
+
+/// CRC-8 (polynomial 0x07). A mistyped character flips at most five neighbouring bits, and a
+/// CRC-8 catches every such burst.
+fn checksum(bytes: &[u8]) -> u8 {
+    let mut crc: u8 = 0;
+    for byte in bytes {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+        }
+    }
+
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenges() -> Vec<Challenge> {
+        let mut challenges = Vec::new();
+        for mode in ModeKind::ALL {
+            for generator in GeneratorKind::ALL {
+                for seed in [0, 1, 0xDEAD_BEEF, u32::MAX] {
+                    challenges.push(Challenge { mode, course: ChallengeCourse::Seeded { generator, seed } });
+                }
+            }
+            for day in [0, 20_000, u32::MAX] {
+                challenges.push(Challenge { mode, course: ChallengeCourse::Daily { day } });
+            }
+        }
+        challenges
+    }
+
+    #[test]
+    fn codes_parse_back_to_their_challenge() {
+        for challenge in challenges() {
+            let code = challenge.encode();
+            assert_eq!(code.len(), CODE_LENGTH + 2);
+            assert_eq!(Challenge::parse(&code), Ok(challenge));
+            assert_eq!(Challenge::parse(&code.to_lowercase().replace('-', "")), Ok(challenge));
+        }
+    }
+
+    #[test]
+    fn mistyped_codes_are_turned_away() {
+        for challenge in challenges() {
+            let code: Vec<char> = challenge.encode().chars().collect();
+
+            for idx in (0..code.len()).filter(|idx| code[*idx] != '-') {
+                let symbol = ALPHABET.iter().position(|symbol| *symbol as char == code[idx]).expect("Failed to find code symbol");
+                let mut typo = code.clone();
+                typo[idx] = ALPHABET[(symbol + 1) % ALPHABET.len()] as char;
+                let typo: String = typo.into_iter().collect();
+
+                assert!(Challenge::parse(&typo).is_err(), "{} was read as {:?}", typo, Challenge::parse(&typo));
+            }
+        }
+    }
+}
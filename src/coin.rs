@@ -0,0 +1,85 @@
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::pipe::PipeGap;
+use crate::world::WorldSpeed;
+
+pub const COIN_SIZE: f32 = 20.0;
+const COINS_PER_PATH: usize = 5;
+
+pub fn coin_image(size: u32) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+    let radius = size as f32 / 2.0;
+
+    image::ImageBuffer::from_fn(size, size, |x, y| {
+        let dx = x as f32 + 0.5 - radius;
+        let dy = y as f32 + 0.5 - radius;
+        let dist = (dx * dx + dy * dy).sqrt();
+
+        if dist > radius {
+            image::Rgba([0, 0, 0, 0])
+        } else if dist > radius - 2.0 {
+            image::Rgba([200, 140, 20, 255])
+        } else {
+            image::Rgba([255, 210, 60, 255])
+        }
+    })
+}
+
+/// Lays coins through pipe gaps and along the line between consecutive gaps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoinManager {
+    pub spawn_chance: f32,
+    last_gap: Option<PipeGap>,
+    distance_since_gap: f32,
+}
+
+impl CoinManager {
+    pub fn new(spawn_chance: f32) -> Self {
+        Self {
+            spawn_chance,
+            last_gap: None,
+            distance_since_gap: 0.0,
+        }
+    }
+
+    /// Centres of the coins to lay for a new gap, rolled from `rng` so a seeded course gets the same ones.
+    pub fn on_gap_spawned(&mut self, gap: &PipeGap, rng: &mut impl Rng) -> Vec<(f32, f32)> {
+        let previous = self.last_gap.replace(*gap);
+        let distance = std::mem::take(&mut self.distance_since_gap);
+        let mut coins = Vec::new();
+
+        if rng.random_range(0.0..1.0) >= self.spawn_chance {
+            return coins;
+        }
+
+        // Pulling the gap coin toward an edge rewards tighter lines.
+        let offset = rng.random_range(-0.35..0.35) * gap.size;
+        coins.push((gap.x + gap.width / 2.0, gap.center_y + offset));
+
+        if let Some(previous) = previous {
+            let start_x = gap.x - distance + previous.width;
+            let end_x = gap.x;
+
+            for idx in 1..=COINS_PER_PATH {
+                let t = idx as f32 / (COINS_PER_PATH + 1) as f32;
+                let eased = t * t * (3.0 - 2.0 * t);
+                let x = start_x + (end_x - start_x) * t;
+                let y = previous.center_y + (gap.center_y - previous.center_y) * eased;
+
+                coins.push((x, y));
+            }
+        }
+
+        coins
+    }
+
+    /// Counts the distance scrolled since the last gap, which the next trail is laid along.
+    pub fn advance(&mut self, world_speed: &WorldSpeed) {
+        self.distance_since_gap += world_speed.value().abs();
+    }
+
+    pub fn reset(&mut self) {
+        self.last_gap = None;
+        self.distance_since_gap = 0.0;
+    }
+}
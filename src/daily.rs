@@ -0,0 +1,139 @@
+/// Days since 1970-01-01 in UTC, so everyone shares the same daily course.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> Option<i64> {
+    let elapsed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+
+    Some((elapsed.as_secs() / 86_400) as i64)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn today() -> Option<i64> {
+    None
+}
+
+/// Calendar date of `day` as `(year, month, day)`, using Howard Hinnant's civil-from-days.
+pub fn civil_date(day: i64) -> (i64, u32, u32) {
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+
+    (y, m, d)
+}
+
+pub fn date_string(day: i64) -> String {
+    let (y, m, d) = civil_date(day);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// Day number of a `YYYY-MM-DD` date, the inverse of `date_string`, using Hinnant's days-from-civil.
+pub fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-');
+    let y: i64 = parts.next()?.parse().ok()?;
+    let m: i64 = parts.next()?.parse().ok()?;
+    let d: i64 = parts.next()?.parse().ok()?;
+
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let day = era * 146_097 + doe - 719_468;
+
+    // Rejects dates like 02-31 that would roll over into the next month.
+    (date_string(day) == date).then_some(day)
+}
+
+/// Course seed for `day`, an FNV-1a hash of its date so it never depends on the rand version.
+pub fn seed(day: i64) -> u64 {
+    let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+    for byte in format!("daily-{}", date_string(day)).bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01B3);
+    }
+
+    hash
+}
+
+/// A small orange flame for the streak counter.
+pub fn streak_icon(size: u32) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+    let half = size as f32 / 2.0;
+
+    image::ImageBuffer::from_fn(size, size, |x, y| {
+        let dx = (x as f32 + 0.5 - half).abs();
+        let t = (y as f32 + 0.5) / size as f32;
+        // Narrow at the tip, round at the base.
+        let width = half * (t * 1.6).min(1.0) * (1.0 - ((t - 0.7).max(0.0) / 0.3).powi(2)).sqrt();
+
+        if dx > width {
+            image::Rgba([0, 0, 0, 0])
+        } else if dx < width * 0.45 && t > 0.45 {
+            image::Rgba([255, 220, 90, 255])
+        } else {
+            image::Rgba([245, 120, 30, 255])
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_dates_match_the_calendar() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(-1), (1969, 12, 31));
+        assert_eq!(civil_date(11_016), (2000, 2, 29));
+        assert_eq!(civil_date(19_782), (2024, 2, 29));
+        assert_eq!(civil_date(20_454), (2026, 1, 1));
+    }
+
+    #[test]
+    fn dates_parse_back_to_their_day() {
+        // Leap years, century years and the ends of the supported range.
+        for year in [0_i64, 1, 1600, 1900, 1969, 1970, 2000, 2023, 2024, 2100, 2400, 9999] {
+            let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+            for month in 1..=12 {
+                let last = match month {
+                    2 if leap => 29,
+                    2 => 28,
+                    4 | 6 | 9 | 11 => 30,
+                    _ => 31,
+                };
+                for day_of_month in [1, last] {
+                    let date = format!("{:04}-{:02}-{:02}", year, month, day_of_month);
+                    let day = parse_date(&date).expect("Failed to parse date");
+                    assert_eq!(date_string(day), date);
+                }
+
+                // The day after a month's last is the next month's first.
+                let last_day = parse_date(&format!("{:04}-{:02}-{:02}", year, month, last)).expect("Failed to parse date");
+                assert_eq!(civil_date(last_day + 1).2, 1, "{}-{:02}", year, month);
+            }
+        }
+
+        // A spread of days across 0000-01-01 to 9999-12-31.
+        for day in (-719_528..=2_932_896).step_by(997) {
+            assert_eq!(parse_date(&date_string(day)), Some(day), "{}", date_string(day));
+        }
+    }
+
+    #[test]
+    fn impossible_dates_are_rejected() {
+        for date in ["2025-02-29", "2100-02-29", "2024-04-31", "2024-13-01", "2024-00-10", "2024-01-00", "2024-1-5", "yesterday"] {
+            assert_eq!(parse_date(date), None, "{}", date);
+        }
+        assert_eq!(parse_date("2000-02-29"), Some(11_016));
+    }
+}
@@ -0,0 +1,312 @@
+use prism::Context;
+use stork::Canvas;
+
+use crate::coin;
+use crate::level::{FinishLine, Level, LevelObstacle};
+use crate::obstacle::Pickup;
+use crate::pipe::{finish_line_image, PipeManager, PipeVariant, MIN_PASSABLE_GAP};
+use crate::powerup::PowerUpKind;
+use crate::ui::{solid, Overlay};
+
+/// Where the timeline's current scroll position is drawn.
+const ORIGIN_X: f32 = 100.0;
+/// Pipes scroll 3px a tick, so one second of timeline is this wide on screen.
+const PIXELS_PER_SECOND: f32 = 3.0 / 0.016;
+const SCROLL_STEP: f32 = 1.0;
+const GAP_SIZE_STEP: f32 = 10.0;
+const MAX_GAP_SIZE: f32 = 400.0;
+const PICKUP_SIZE: f32 = 24.0;
+/// Size of the bin in the top right corner that deletes a selected pair.
+const TRASH_SIZE: f32 = 48.0;
+/// Seconds between starting a test play and the first pair spawning.
+const TEST_LEAD: f32 = 1.0;
+
+/// What the game should do after an editor key or press.
+#[derive(Debug, Clone)]
+pub enum EditorAction {
+    None,
+    Save,
+    TestPlay(Level),
+    Exit,
+}
+
+/// Timeline editor for authored levels, driven by the pointer and keyboard.
+///
+/// Pressing empty space places a pair, pressing a pair selects it, and the next press moves it
+/// there or, on the bin, deletes it. "a"/"d" scroll the timeline. "-"/"=" resize the gap of the
+/// pair under the last press, "c" cycles its pickup, "v" its variant and "f" the finish line.
+/// "p" test-plays from the last press, "s" saves and "m" leaves.
+#[derive(Debug)]
+pub struct Editor {
+    pub level: Level,
+    pub path: String,
+    scroll: f32,
+    pointer: (f32, f32),
+    grabbed: Option<usize>,
+    overlay: Overlay,
+}
+
+impl Editor {
+    pub fn new(level: Level, path: &str) -> Self {
+        Self {
+            level,
+            path: path.to_string(),
+            scroll: 0.0,
+            pointer: (2.0, 300.0),
+            grabbed: None,
+            overlay: Overlay::new("editor"),
+        }
+    }
+
+    fn visible_seconds(canvas_width: f32) -> f32 {
+        (canvas_width - ORIGIN_X) / PIXELS_PER_SECOND
+    }
+
+    fn screen_x(&self, at: f32) -> f32 {
+        ORIGIN_X + (at - self.scroll) * PIXELS_PER_SECOND
+    }
+
+    fn timeline_at(&self, screen_x: f32) -> f32 {
+        (self.scroll + (screen_x - ORIGIN_X) / PIXELS_PER_SECOND).max(0.0)
+    }
+
+    fn trash_position(canvas_size: (f32, f32)) -> (f32, f32) {
+        (canvas_size.0 - TRASH_SIZE - 12.0, 12.0)
+    }
+
+    fn in_trash(position: (f32, f32), canvas_size: (f32, f32)) -> bool {
+        let (x, y) = Self::trash_position(canvas_size);
+        (x..=x + TRASH_SIZE).contains(&position.0) && (y..=y + TRASH_SIZE).contains(&position.1)
+    }
+
+    /// The pair whose pipes cover the pointer, if any.
+    fn hovered(&self, pipe_width: f32) -> Option<usize> {
+        let reach = pipe_width / 2.0 / PIXELS_PER_SECOND;
+
+        self.level.obstacles.iter()
+            .enumerate()
+            .filter(|(_, obstacle)| (obstacle.at - self.pointer.0).abs() <= reach)
+            .min_by(|(_, a), (_, b)| (a.at - self.pointer.0).abs().total_cmp(&(b.at - self.pointer.0).abs()))
+            .map(|(idx, _)| idx)
+    }
+
+    fn target(&self, pipe_width: f32) -> Option<usize> {
+        self.grabbed.or_else(|| self.hovered(pipe_width))
+    }
+
+    /// Places, selects, moves or deletes a pair for a press at `position` on the canvas.
+    pub fn handle_press(&mut self, position: (f32, f32), canvas_size: (f32, f32), pipe_width: f32) {
+        if Self::in_trash(position, canvas_size) {
+            if let Some(idx) = self.grabbed.take() {
+                self.level.obstacles.remove(idx);
+            }
+            return;
+        }
+
+        self.pointer = (self.timeline_at(position.0), position.1.clamp(0.0, canvas_size.1));
+        let hovered = self.hovered(pipe_width);
+
+        match self.grabbed.take() {
+            Some(idx) if hovered == Some(idx) => {}
+            Some(idx) => {
+                let obstacle = &mut self.level.obstacles[idx];
+                obstacle.at = self.pointer.0;
+                obstacle.gap_y = self.pointer.1;
+            }
+            None if hovered.is_some() => self.grabbed = hovered,
+            None => self.level.obstacles.push(LevelObstacle {
+                at: self.pointer.0,
+                gap_y: self.pointer.1,
+                gap_size: None,
+                variant: PipeVariant::Static,
+                pickup: None,
+            }),
+        }
+
+        if self.grabbed.is_none() {
+            self.level.sort();
+        }
+    }
+
+    pub fn handle_key(&mut self, key: &str, pipe_width: f32) -> EditorAction {
+        match key {
+            "a" => self.scroll = (self.scroll - SCROLL_STEP).max(0.0),
+            "d" => self.scroll += SCROLL_STEP,
+            "-" | "=" => {
+                if let Some(idx) = self.target(pipe_width) {
+                    let step = if key == "-" { -GAP_SIZE_STEP } else { GAP_SIZE_STEP };
+                    let obstacle = &mut self.level.obstacles[idx];
+                    let size = obstacle.gap_size.unwrap_or(self.level.gap_size) + step;
+                    obstacle.gap_size = Some(size.clamp(MIN_PASSABLE_GAP, MAX_GAP_SIZE));
+                }
+            }
+            "c" => {
+                if let Some(idx) = self.target(pipe_width) {
+                    let obstacle = &mut self.level.obstacles[idx];
+                    obstacle.pickup = next_pickup(obstacle.pickup);
+                }
+            }
+            "v" => {
+                if let Some(idx) = self.target(pipe_width) {
+                    let gap_size = self.level.gap_size;
+                    let obstacle = &mut self.level.obstacles[idx];
+                    obstacle.variant = next_variant(obstacle.variant, obstacle.gap_size.unwrap_or(gap_size));
+                }
+            }
+            "f" => {
+                let reach = pipe_width / 2.0 / PIXELS_PER_SECOND;
+                self.level.finish = match self.level.finish {
+                    Some(finish) if (finish.at - self.pointer.0).abs() <= reach => None,
+                    _ => Some(FinishLine { at: self.pointer.0 }),
+                };
+            }
+            "p" => {
+                self.grabbed = None;
+                self.level.sort();
+                return EditorAction::TestPlay(self.level.starting_at(self.pointer.0, TEST_LEAD));
+            }
+            "s" => {
+                self.level.sort();
+                return EditorAction::Save;
+            }
+            "m" => {
+                self.grabbed = None;
+                self.level.sort();
+                return EditorAction::Exit;
+            }
+            _ => {}
+        }
+
+        EditorAction::None
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, pipe_manager: &PipeManager, canvas_size: (f32, f32)) {
+        self.clear(canvas);
+
+        let (pipe_width, pipe_height) = (pipe_manager.pipe_width, pipe_manager.pipe_height);
+        let visible = Self::visible_seconds(canvas_size.0);
+        let hovered = self.target(pipe_width);
+
+        for second in self.scroll.ceil() as u32..=(self.scroll + visible) as u32 {
+            let x = self.screen_x(second as f32);
+            self.overlay.add(ctx, canvas, &format!("editor_tick_{}", second), solid([255, 255, 255, 200]), (x - 1.0, 0.0), (2.0, 12.0));
+        }
+
+        for idx in 0..self.level.obstacles.len() {
+            let obstacle = self.level.obstacles[idx].clone();
+            if obstacle.at < self.scroll - 1.0 || obstacle.at > self.scroll + visible + 1.0 {
+                continue;
+            }
+
+            let x = self.screen_x(obstacle.at) - pipe_width / 2.0;
+            let size = obstacle.gap_size.unwrap_or(self.level.gap_size);
+            let (top, bottom) = (obstacle.gap_y - size / 2.0, obstacle.gap_y + size / 2.0);
+
+            if hovered == Some(idx) {
+                let outline = if self.grabbed.is_some() { [255, 210, 60, 255] } else { [255, 255, 255, 255] };
+                self.overlay.add(ctx, canvas, &format!("editor_outline_{}", idx), solid(outline), (x - 4.0, 0.0), (pipe_width + 8.0, canvas_size.1));
+            }
+
+            self.overlay.add(ctx, canvas, &format!("editor_top_{}", idx), pipe_manager.toppipe_image.clone(), (x, top - pipe_height), (pipe_width, pipe_height));
+            self.overlay.add(ctx, canvas, &format!("editor_bottom_{}", idx), pipe_manager.bottompipe_image.clone(), (x, bottom), (pipe_width, pipe_height));
+
+            if obstacle.variant != PipeVariant::Static {
+                self.overlay.add(ctx, canvas, &format!("editor_variant_{}", idx), solid([190, 110, 255, 255]), (x, obstacle.gap_y - 2.0), (pipe_width, 4.0));
+            }
+
+            let icon = match obstacle.pickup {
+                Some(Pickup::Coin) => Some(coin::coin_image(PICKUP_SIZE as u32)),
+                Some(Pickup::PowerUp(kind)) => Some(kind.icon(PICKUP_SIZE as u32)),
+                None => None,
+            };
+            if let Some(icon) = icon {
+                let position = (x + pipe_width / 2.0 - PICKUP_SIZE / 2.0, obstacle.gap_y - PICKUP_SIZE / 2.0);
+                self.overlay.add(ctx, canvas, &format!("editor_pickup_{}", idx), icon, position, (PICKUP_SIZE, PICKUP_SIZE));
+            }
+        }
+
+        if let Some(finish) = self.level.finish {
+            let x = self.screen_x(finish.at);
+            self.overlay.add(ctx, canvas, "editor_finish", finish_line_image(24, 48), (x - 12.0, 0.0), (24.0, canvas_size.1 - 112.0));
+        }
+
+        let trash = if self.grabbed.is_some() { [230, 60, 60, 230] } else { [120, 120, 120, 160] };
+        self.overlay.add(ctx, canvas, "editor_trash", solid(trash), Self::trash_position(canvas_size), (TRASH_SIZE, TRASH_SIZE));
+
+        let (x, y) = (self.screen_x(self.pointer.0), self.pointer.1);
+        self.overlay.add(ctx, canvas, "editor_pointer_h", solid([255, 60, 60, 255]), (x - 12.0, y - 1.0), (24.0, 2.0));
+        self.overlay.add(ctx, canvas, "editor_pointer_v", solid([255, 60, 60, 255]), (x - 1.0, y - 12.0), (2.0, 24.0));
+    }
+
+    pub fn clear(&mut self, canvas: &mut Canvas) {
+        self.overlay.clear(canvas);
+    }
+}
+
+fn next_pickup(pickup: Option<Pickup>) -> Option<Pickup> {
+    match pickup {
+        None => Some(Pickup::Coin),
+        Some(Pickup::Coin) => Some(Pickup::PowerUp(PowerUpKind::ALL[0])),
+        Some(Pickup::PowerUp(kind)) => {
+            let idx = PowerUpKind::ALL.iter().position(|other| *other == kind).unwrap_or(0);
+            PowerUpKind::ALL.get(idx + 1).map(|next| Pickup::PowerUp(*next))
+        }
+    }
+}
+
+fn next_variant(variant: PipeVariant, gap_size: f32) -> PipeVariant {
+    match variant {
+        PipeVariant::Static => PipeVariant::Bobbing { amplitude: 40.0, period: 2.5 },
+        PipeVariant::Bobbing { .. } => PipeVariant::Breathing {
+            min_size: (gap_size * 0.75).max(MIN_PASSABLE_GAP),
+            period: 2.0,
+        },
+        PipeVariant::Breathing { .. } => PipeVariant::Drifting { rate: 30.0 },
+        PipeVariant::Drifting { .. } => PipeVariant::Static,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANVAS: (f32, f32) = (800.0, 600.0);
+    const PIPE_WIDTH: f32 = 50.0;
+
+    fn editor() -> Editor {
+        Editor::new(Level::empty("Test", 220.0), "test.json")
+    }
+
+    #[test]
+    fn presses_place_move_and_delete_pairs() {
+        let mut editor = editor();
+
+        editor.handle_press((ORIGIN_X + PIXELS_PER_SECOND, 250.0), CANVAS, PIPE_WIDTH);
+        assert_eq!(editor.level.obstacles.len(), 1);
+        assert!((editor.level.obstacles[0].at - 1.0).abs() < 0.01);
+        assert_eq!(editor.level.obstacles[0].gap_y, 250.0);
+
+        // Select the pair, then press where it should go.
+        editor.handle_press((ORIGIN_X + PIXELS_PER_SECOND + 10.0, 400.0), CANVAS, PIPE_WIDTH);
+        assert_eq!(editor.level.obstacles.len(), 1);
+        editor.handle_press((ORIGIN_X + 3.0 * PIXELS_PER_SECOND, 300.0), CANVAS, PIPE_WIDTH);
+        assert!((editor.level.obstacles[0].at - 3.0).abs() < 0.01);
+        assert_eq!(editor.level.obstacles[0].gap_y, 300.0);
+
+        // Select it again and drop it in the bin.
+        editor.handle_press((ORIGIN_X + 3.0 * PIXELS_PER_SECOND, 100.0), CANVAS, PIPE_WIDTH);
+        let (x, y) = Editor::trash_position(CANVAS);
+        editor.handle_press((x + TRASH_SIZE / 2.0, y + TRASH_SIZE / 2.0), CANVAS, PIPE_WIDTH);
+        assert!(editor.level.obstacles.is_empty());
+    }
+
+    #[test]
+    fn bin_ignores_presses_with_nothing_selected() {
+        let mut editor = editor();
+        editor.handle_press((ORIGIN_X, 250.0), CANVAS, PIPE_WIDTH);
+
+        let (x, y) = Editor::trash_position(CANVAS);
+        editor.handle_press((x + 1.0, y + 1.0), CANVAS, PIPE_WIDTH);
+        assert_eq!(editor.level.obstacles.len(), 1);
+    }
+}
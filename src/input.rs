@@ -0,0 +1,202 @@
+use prism::Context;
+use prism::event::Key;
+use prism::canvas::{Image, ShapeType};
+use stork::{Canvas, GameObject, Action, Target, GameEvent};
+
+const PROBE_POSITION: (f32, f32) = (-100.0, -100.0);
+/// Width of each pointer strip, and so how finely a press is placed.
+const POINTER_CELL: f32 = 10.0;
+
+/// Turns key presses into polled input without touching `Game::on_event`.
+///
+/// Each key gets a hidden probe object that the canvas nudges on `KeyPress`; polling reads and
+/// clears the nudge.
+#[derive(Debug, Default)]
+pub struct InputProbes {
+    keys: Vec<String>,
+}
+
+impl InputProbes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn probe_name(name: &str) -> String {
+        format!("input_probe_{}", name)
+    }
+
+    pub fn register_char(&mut self, ctx: &mut Context, canvas: &mut Canvas, key: &str) {
+        self.register(ctx, canvas, Key::Character(key.to_string().into()), key);
+    }
+
+    /// Registers `key`, reported by `poll` as `name`.
+    pub fn register(&mut self, ctx: &mut Context, canvas: &mut Canvas, key: Key, name: &str) {
+        if self.keys.iter().any(|registered| registered == name) {
+            return;
+        }
+
+        let probe_name = Self::probe_name(name);
+
+        canvas.add_game_object(probe_name.clone(), probe(ctx, &probe_name, (1.0, 1.0), PROBE_POSITION));
+
+        canvas.add_event(
+            GameEvent::KeyPress {
+                key,
+                action: Action::ApplyMomentum {
+                    target: Target::ById(probe_name.clone()),
+                    value: (0.0, 1.0)
+                },
+                target: Target::ById(probe_name.clone())
+            },
+            Target::ById(probe_name)
+        );
+
+        self.keys.push(name.to_string());
+    }
+
+    /// Returns the names of keys pressed since the last poll.
+    pub fn poll(&mut self, canvas: &mut Canvas) -> Vec<String> {
+        let mut pressed = Vec::new();
+
+        for name in &self.keys {
+            if let Some(probe) = canvas.get_game_object_mut(&Self::probe_name(name)) {
+                if probe.momentum != (0.0, 0.0) {
+                    pressed.push(name.clone());
+                }
+
+                probe.momentum = (0.0, 0.0);
+                probe.position = PROBE_POSITION;
+            }
+        }
+
+        pressed
+    }
+}
+
+/// Turns pointer presses into polled canvas positions, the same way `InputProbes` does for keys.
+///
+/// While armed the canvas is covered by invisible column and row strips. Stork's hit test nudges
+/// every strip under a press, so the one column and one row that moved give its position.
+#[derive(Debug, Default)]
+pub struct PointerProbes {
+    columns: usize,
+    rows: usize,
+}
+
+impl PointerProbes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn column_name(idx: usize) -> String {
+        format!("pointer_probe_column_{}", idx)
+    }
+
+    fn row_name(idx: usize) -> String {
+        format!("pointer_probe_row_{}", idx)
+    }
+
+    fn column_position(idx: usize) -> (f32, f32) {
+        (idx as f32 * POINTER_CELL, 0.0)
+    }
+
+    fn row_position(idx: usize) -> (f32, f32) {
+        (0.0, idx as f32 * POINTER_CELL)
+    }
+
+    /// Covers the canvas with strips so presses on it are reported by `poll`.
+    pub fn arm(&mut self, ctx: &mut Context, canvas: &mut Canvas, canvas_size: (f32, f32)) {
+        self.disarm(canvas);
+
+        self.columns = (canvas_size.0 / POINTER_CELL).ceil() as usize;
+        self.rows = (canvas_size.1 / POINTER_CELL).ceil() as usize;
+
+        for idx in 0..self.columns {
+            Self::add_strip(ctx, canvas, Self::column_name(idx), (POINTER_CELL, canvas_size.1), Self::column_position(idx));
+        }
+        for idx in 0..self.rows {
+            Self::add_strip(ctx, canvas, Self::row_name(idx), (canvas_size.0, POINTER_CELL), Self::row_position(idx));
+        }
+    }
+
+    /// Removes the strips so presses go back to whatever is underneath.
+    pub fn disarm(&mut self, canvas: &mut Canvas) {
+        for idx in 0..self.columns {
+            canvas.remove_game_object(&Self::column_name(idx));
+        }
+        for idx in 0..self.rows {
+            canvas.remove_game_object(&Self::row_name(idx));
+        }
+
+        self.columns = 0;
+        self.rows = 0;
+    }
+
+    fn add_strip(ctx: &mut Context, canvas: &mut Canvas, name: String, size: (f32, f32), position: (f32, f32)) {
+        canvas.add_game_object(name.clone(), probe(ctx, &name, size, position));
+
+        canvas.add_event(
+            GameEvent::MousePress {
+                action: Action::ApplyMomentum {
+                    target: Target::ById(name.clone()),
+                    value: (0.0, 1.0)
+                },
+                target: Target::ById(name.clone())
+            },
+            Target::ById(name)
+        );
+    }
+
+    /// Returns the centre of the cell pressed since the last poll, if any.
+    pub fn poll(&mut self, canvas: &mut Canvas) -> Option<(f32, f32)> {
+        let column = Self::pressed(canvas, (0..self.columns).map(|idx| (Self::column_name(idx), Self::column_position(idx))));
+        let row = Self::pressed(canvas, (0..self.rows).map(|idx| (Self::row_name(idx), Self::row_position(idx))));
+
+        match (column, row) {
+            (Some(column), Some(row)) => Some((
+                (column as f32 + 0.5) * POINTER_CELL,
+                (row as f32 + 0.5) * POINTER_CELL,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Index of the first nudged strip, resetting every strip on the way.
+    fn pressed(canvas: &mut Canvas, strips: impl Iterator<Item = (String, (f32, f32))>) -> Option<usize> {
+        let mut pressed = None;
+
+        for (idx, (name, position)) in strips.enumerate() {
+            if let Some(probe) = canvas.get_game_object_mut(&name) {
+                if probe.momentum != (0.0, 0.0) && pressed.is_none() {
+                    pressed = Some(idx);
+                }
+
+                probe.momentum = (0.0, 0.0);
+                probe.position = position;
+            }
+        }
+
+        pressed
+    }
+}
+
+/// An invisible object for the canvas to nudge.
+fn probe(ctx: &mut Context, name: &str, size: (f32, f32), position: (f32, f32)) -> GameObject {
+    let img_obj = Image {
+        shape: ShapeType::Rectangle(0.0, size, 0.0),
+        image: image::ImageBuffer::from_pixel(1, 1, image::Rgba([0, 0, 0, 0])).into(),
+        color: None
+    };
+
+    GameObject::new_rect(
+        ctx,
+        name.to_string(),
+        img_obj,
+        size,
+        position,
+        vec!["input".to_string()],
+        (0.0, 0.0),
+        (1.0, 1.0),
+        0.0,
+    )
+}
@@ -0,0 +1,187 @@
+use serde::{Serialize, Deserialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::replay::Replay;
+use crate::storage::Storage;
+
+/// Where the reference server listens unless `ORANGE_LEADERBOARD` says otherwise.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Entries fetched after each submitted run.
+pub const TOP_COUNT: usize = 5;
+
+/// The name this install submits scores under, made up on first launch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+}
+
+impl Profile {
+    const KEY: &'static str = "FlappyProfile";
+
+    pub fn load_or_create(storage: &Storage) -> Self {
+        if let Some(profile) = storage.get(Self::KEY) {
+            return profile;
+        }
+
+        let profile = Self { name: format!("player-{:04x}", rand::random::<u16>()) };
+        storage.set(Self::KEY, &profile);
+        profile
+    }
+}
+
+/// A finished run sent to the leaderboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Submission {
+    /// Which leaderboard, such as "classic" or "daily-2025-06-01".
+    pub board: String,
+    pub player: String,
+    pub score: usize,
+    pub replay: Replay,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub rank: usize,
+    pub player: String,
+    pub score: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Standings {
+    pub board: String,
+    pub top: Vec<Entry>,
+    /// The player's own best entry, wherever it ranks.
+    pub player: Option<Entry>,
+    pub total: usize,
+}
+
+/// One line of JSON from client to server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Request {
+    Submit { submission: Submission },
+    Top { board: String, limit: usize, player: String },
+}
+
+/// One line of JSON from server to client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Response {
+    Submitted { rank: usize, total: usize },
+    Top { entries: Vec<Entry>, player: Option<Entry>, total: usize },
+    Rejected { reason: String },
+}
+
+/// Talks to a leaderboard, whatever the transport.
+pub trait LeaderboardClient: std::fmt::Debug + Send {
+    /// Sends a run and returns its rank on the board.
+    fn submit(&mut self, submission: &Submission) -> Result<usize, String>;
+
+    /// The best `limit` entries on `board` and where `player` stands.
+    fn standings(&mut self, board: &str, limit: usize, player: &str) -> Result<Standings, String>;
+}
+
+/// A leaderboard spoken to with one JSON line each way per request over TCP.
+#[derive(Debug, Clone)]
+pub struct TcpLeaderboard {
+    pub address: String,
+}
+
+impl TcpLeaderboard {
+    pub fn new(address: &str) -> Self {
+        Self { address: address.to_string() }
+    }
+
+    /// The server in `ORANGE_LEADERBOARD`, or the local reference server.
+    pub fn from_env() -> Self {
+        Self::new(&std::env::var("ORANGE_LEADERBOARD").unwrap_or_else(|_| DEFAULT_ADDRESS.to_string()))
+    }
+
+    fn request(&self, request: &Request) -> Result<Response, String> {
+        let address = self.address.parse()
+            .map_err(|e| format!("Invalid leaderboard address {}: {}", self.address, e))?;
+        let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)
+            .map_err(|e| format!("Failed to reach leaderboard at {}: {}", self.address, e))?;
+        stream.set_read_timeout(Some(TIMEOUT)).map_err(|e| format!("Failed to set timeout: {}", e))?;
+
+        let mut line = serde_json::to_vec(request).expect("Failed to serialize request");
+        line.push(b'\n');
+        stream.write_all(&line).map_err(|e| format!("Failed to send to leaderboard: {}", e))?;
+
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply)
+            .map_err(|e| format!("Failed to read from leaderboard: {}", e))?;
+
+        serde_json::from_str(&reply).map_err(|e| format!("Invalid reply from leaderboard: {}", e))
+    }
+}
+
+impl LeaderboardClient for TcpLeaderboard {
+    fn submit(&mut self, submission: &Submission) -> Result<usize, String> {
+        match self.request(&Request::Submit { submission: submission.clone() })? {
+            Response::Submitted { rank, .. } => Ok(rank),
+            Response::Rejected { reason } => Err(format!("Score rejected: {}", reason)),
+            other => Err(format!("Unexpected reply from leaderboard: {:?}", other)),
+        }
+    }
+
+    fn standings(&mut self, board: &str, limit: usize, player: &str) -> Result<Standings, String> {
+        let request = Request::Top { board: board.to_string(), limit, player: player.to_string() };
+
+        match self.request(&request)? {
+            Response::Top { entries, player, total } => Ok(Standings { board: board.to_string(), top: entries, player, total }),
+            Response::Rejected { reason } => Err(reason),
+            other => Err(format!("Unexpected reply from leaderboard: {:?}", other)),
+        }
+    }
+}
+
+/// Runs leaderboard requests on a background thread so a slow server never stalls a frame.
+#[derive(Debug)]
+pub struct Leaderboard {
+    requests: mpsc::Sender<Submission>,
+    results: mpsc::Receiver<Result<Standings, String>>,
+}
+
+impl Leaderboard {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(mut client: Box<dyn LeaderboardClient>) -> Self {
+        let (requests, pending) = mpsc::channel::<Submission>();
+        let (finished, results) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for submission in pending {
+                let result = client.submit(&submission)
+                    .and_then(|_| client.standings(&submission.board, TOP_COUNT, &submission.player));
+
+                if finished.send(result).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Self { requests, results }
+    }
+
+    /// Without threads there is nobody to send to; submissions are dropped.
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(_client: Box<dyn LeaderboardClient>) -> Self {
+        let (requests, _) = mpsc::channel();
+        let (_, results) = mpsc::channel();
+
+        Self { requests, results }
+    }
+
+    pub fn submit(&self, submission: Submission) {
+        let _ = self.requests.send(submission);
+    }
+
+    /// Standings for runs submitted since the last poll.
+    pub fn poll(&self) -> Vec<Result<Standings, String>> {
+        self.results.try_iter().collect()
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Serialize, Deserialize};
+
+use crate::obstacle::{AuthoredCourse, ObstacleSpec, Pickup};
+use crate::physics::Physics;
+use crate::pipe::{PipePair, PipeVariant};
+use crate::reach::{self, ScheduledPair, SolverConfig, Unreachable};
+
+pub const LEVEL_VERSION: u32 = 1;
+
+/// Levels shipped with the game, by name.
+pub const EMBEDDED: [(&str, &str); 1] = [
+    ("tutorial", include_str!("../assets/levels/tutorial.json")),
+];
+
+fn default_variant() -> PipeVariant {
+    PipeVariant::Static
+}
+
+/// One pipe pair on the course timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelObstacle {
+    /// Seconds from the start of the course when the pair spawns.
+    pub at: f32,
+    pub gap_y: f32,
+    /// Falls back to the level's `gap_size`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap_size: Option<f32>,
+    #[serde(default = "default_variant")]
+    pub variant: PipeVariant,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pickup: Option<Pickup>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FinishLine {
+    /// Seconds from the start of the course when the finish line spawns.
+    pub at: f32,
+}
+
+/// A hand-made course, stored as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Level {
+    pub version: u32,
+    pub name: String,
+    pub gap_size: f32,
+    pub obstacles: Vec<LevelObstacle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish: Option<FinishLine>,
+}
+
+impl Level {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut level: Level = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid level file: {}", e))?;
+
+        if level.version != LEVEL_VERSION {
+            return Err(format!("Level '{}' is version {}, expected {}", level.name, level.version, LEVEL_VERSION));
+        }
+
+        level.sort();
+        Ok(level)
+    }
+
+    pub fn empty(name: &str, gap_size: f32) -> Self {
+        Self {
+            version: LEVEL_VERSION,
+            name: name.to_string(),
+            gap_size,
+            obstacles: Vec::new(),
+            finish: None,
+        }
+    }
+
+    /// Puts the timeline back in spawn order.
+    pub fn sort(&mut self) {
+        self.obstacles.sort_by(|a, b| a.at.total_cmp(&b.at));
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read level {}: {}", path, e))?;
+
+        Self::from_json(&json)
+    }
+
+    pub fn embedded(name: &str) -> Option<Self> {
+        EMBEDDED.iter()
+            .find(|(embedded, _)| *embedded == name)
+            .map(|(_, json)| Self::from_json(json).expect("Failed to parse embedded level"))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize level")
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_json())
+            .map_err(|e| format!("Failed to write level {}: {}", path, e))
+    }
+
+    /// The rest of the course from `start` seconds in, with the first pair `lead` seconds away.
+    pub fn starting_at(&self, start: f32, lead: f32) -> Self {
+        let shift = lead - start;
+        let obstacles = self.obstacles.iter()
+            .filter(|obstacle| obstacle.at >= start)
+            .map(|obstacle| LevelObstacle { at: obstacle.at + shift, ..obstacle.clone() })
+            .collect();
+
+        Self {
+            version: self.version,
+            name: self.name.clone(),
+            gap_size: self.gap_size,
+            obstacles,
+            finish: self.finish.filter(|finish| finish.at >= start).map(|finish| FinishLine { at: finish.at + shift }),
+        }
+    }
+
+    /// Turns the timeline into a course `PipeManager` can play back.
+    pub fn course(&self) -> AuthoredCourse {
+        let mut previous_at = 0.0;
+        let obstacles = self.obstacles.iter().map(|obstacle| {
+            let spec = ObstacleSpec {
+                gap_y: obstacle.gap_y,
+                gap_size: obstacle.gap_size.unwrap_or(self.gap_size),
+                variant: obstacle.variant,
+                delay: (obstacle.at - previous_at).max(0.0),
+                pickup: obstacle.pickup,
+            };
+            previous_at = obstacle.at;
+            spec
+        }).collect();
+
+        let course = AuthoredCourse::new(&self.name, obstacles, false);
+        match self.finish {
+            Some(finish) => course.with_finish((finish.at - previous_at).max(0.0)),
+            None => course,
+        }
+    }
+
+    /// Checks a bird starting the run level in the middle of the screen can clear every pair.
+    pub fn validate(&self, physics: &Physics, config: &SolverConfig, canvas_width: f32) -> Result<(), Unreachable> {
+        let course: Vec<ScheduledPair> = self.obstacles.iter().enumerate().map(|(idx, obstacle)| {
+            ScheduledPair {
+                tick: (obstacle.at / physics.tick).round() as u32,
+                pair: PipePair {
+                    id: idx as u32,
+                    x: canvas_width + 100.0,
+                    base_gap_y: obstacle.gap_y,
+                    gap_size: obstacle.gap_size.unwrap_or(self.gap_size),
+                    variant: obstacle.variant,
+                    age: 0.0,
+                },
+            }
+        }).collect();
+
+        reach::validate_course(physics, config, &[(300.0, 0.0)], &course)
+    }
+}
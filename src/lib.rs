@@ -1,20 +1,92 @@
 use prism::drawable::{Drawable, Component, SizedTree};
-use prism::event::{OnEvent, Event, Key};
+use prism::event::{OnEvent, Event};
 use prism::{Context, canvas::{Image, ShapeType}};
 use prism::layout::{SizeRequest, Area};
 
-use stork::{Canvas, GameObject, Action, Target, GameEvent, AnimatedSprite};
+use stork::{Canvas, GameObject};
 
+use serde::{Serialize, Deserialize};
+
+mod animation;
+mod atlas;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod background;
+mod bird;
+#[cfg(not(target_arch = "wasm32"))]
+mod bot;
+mod clock;
+mod code;
+mod coin;
+mod daily;
+mod editor;
+mod input;
+pub mod leaderboard;
+mod level;
+pub mod mode;
+mod mode_select;
+pub mod net;
+mod obstacle;
+pub mod physics;
+mod pickup;
 mod pipe;
+mod powerup;
+mod practice;
+mod reach;
+mod records;
+pub mod replay;
+mod rng;
+pub mod rollback;
 mod score;
+mod scroller;
+mod shop;
+pub mod sim;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod simulate;
+mod skin;
+mod snapshot;
+mod storage;
+mod ui;
+pub mod verify;
+mod versus;
+mod wallet;
+mod world;
 
-use pipe::PipeManager;
-use score::ScoreManager;
+use atlas::Atlas;
+use bird::Bird;
+use clock::FixedStep;
+use code::{Challenge, ChallengeCourse, CODE_LENGTH};
+use editor::{Editor, EditorAction};
+use input::{InputProbes, PointerProbes};
+use leaderboard::{Leaderboard, Profile, Standings, Submission, TcpLeaderboard};
+use level::Level;
+use mode::ModeKind;
+use mode_select::ModeSelect;
+use net::{Message, NetEvent, NetSession, Online, DEFAULT_PORT};
+use obstacle::{ClassicGenerator, GeneratorKind};
+use physics::Physics;
+use pickup::PickupSprites;
+use pipe::{finish_line_image, PipeManager};
+use practice::Practice;
+use reach::SolverConfig;
+use rng::SeededRng;
+use records::{Records, RunEntry, RunLog};
+use replay::ReplayRecorder;
+use score::{DigitStyle, ScoreManager};
+use scroller::ScrollingStrip;
+use sim::{Ending, Player, Simulation, StepEvents, Tuning};
+use shop::Shop;
+use skin::{BackgroundSkin, BirdSkin, Cosmetics, Skin};
+use snapshot::{GameSnapshot, SavedRun};
+use storage::Storage;
+use versus::{Versus, RIVAL_KEY, RIVAL_NAME};
+use wallet::Wallet;
 
 //AVAILABLE IMAGES
     //bg.png
     //bullet.png
     //player.pngk
+    //alien.png
+    //background.png
     //flappybird.png
     //toppipe.png
     //bottompipe.png
@@ -30,17 +102,81 @@ use score::ScoreManager;
     //8.png
     //9.png
 
+//AVAILABLE ATLASES
+    //atlas.png + atlas.json
+
 //AVAILABLE GIFs
     //flappbird.gif
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Screen {
+    Playing,
+    Shop,
+    CourseComplete,
+    Editor,
+    CodeEntry,
+    ModeSelect,
+    Resume,
+    Lobby,
+    GameOver,
+}
+
+/// What the pipes are laid out from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Course {
+    Endless,
+    /// The shared course for a day, in days since the epoch.
+    Daily(i64),
+    /// An entry of `level::EMBEDDED`.
+    Level(usize),
+    /// A generated course replayed from a challenge code.
+    Seeded { generator: GeneratorKind, seed: u32 },
+}
+
+/// Where the editor loads and saves its level.
+const EDITOR_LEVEL_PATH: &str = "custom_level.json";
+
+/// Ticks between saves of the run in progress, about a second.
+const AUTOSAVE_TICKS: u32 = 62;
+
+/// Top of the daily streak counter, clear of the HUD row above it.
+const STREAK_Y: f32 = 60.0;
+
 #[derive(Debug)]
 pub struct Game {
     canvas: Canvas,
     canvas_size: (f32, f32),
-    pipe_manager: PipeManager,
-    score_manager: ScoreManager,
-    base_width: f32,
-    game_over: bool,
+    bird: Bird,
+    /// The run itself; everything on the canvas is drawn from it.
+    sim: Simulation,
+    pickup_sprites: PickupSprites,
+    wallet: Wallet,
+    cosmetics: Cosmetics,
+    shop: Shop,
+    atlas: Atlas,
+    storage: Storage,
+    input: InputProbes,
+    pointer: PointerProbes,
+    clock: FixedStep,
+    scrollers: Vec<ScrollingStrip>,
+    physics: Physics,
+    course: Course,
+    mode_select: ModeSelect,
+    practice: Practice,
+    versus: Option<Versus>,
+    records: Records,
+    replay: ReplayRecorder,
+    leaderboard: Leaderboard,
+    profile: Profile,
+    editor: Option<Editor>,
+    testing: bool,
+    code_input: String,
+    online: Option<Online>,
+    /// The address being typed in the lobby, once "j" has been pressed.
+    address_input: Option<String>,
+    saved_run: Option<SavedRun>,
+    autosave_ticks: u32,
+    screen: Screen,
 }
 
 ///! No adding code into this impl, keep it how it is :)
@@ -76,279 +212,1254 @@ impl Component for Game {
 }
 
 impl Game {
+    /// Plays as many ticks as are due, however often the app calls in.
     fn update_game(&mut self, ctx: &mut Context) {
-        self.check_collisions(ctx);
-        
-        if self.game_over {
+        // Presses wait on the probes until a tick polls them, so none fall between ticks.
+        for _ in 0..self.clock.ticks_due() {
+            self.update_tick(ctx);
+        }
+    }
+
+    fn update_tick(&mut self, ctx: &mut Context) {
+        let mut keys = self.input.poll(&mut self.canvas);
+
+        for result in self.leaderboard.poll() {
+            match result {
+                Ok(standings) => self.print_standings(&standings),
+                Err(e) => println!("> {}", e),
+            }
+        }
+
+        if self.screen != Screen::Playing {
+            if let Some(versus) = &self.versus {
+                versus.hold(&mut self.canvas);
+            }
+        }
+
+        if self.screen == Screen::Shop {
+            self.update_shop(ctx, keys);
             return;
         }
-        
-        self.pipe_manager.update(ctx, &mut self.canvas);
-        self.update_base_positions();
-        self.score_manager.check_score(&self.canvas, self.pipe_manager.pipe_counter, self.pipe_manager.pipe_width);
-        self.score_manager.update_display(ctx, &mut self.canvas);
-        self.check_ceiling_collision();
+
+        if self.screen == Screen::CourseComplete {
+            self.update_course_complete(keys);
+            return;
+        }
+
+        if self.screen == Screen::Editor {
+            self.update_editor(ctx, keys);
+            return;
+        }
+
+        if self.screen == Screen::CodeEntry {
+            self.update_code_entry(ctx, keys);
+            return;
+        }
+
+        if self.screen == Screen::ModeSelect {
+            self.update_mode_select(ctx, keys);
+            return;
+        }
+
+        if self.screen == Screen::Resume {
+            self.update_resume(ctx, keys);
+            return;
+        }
+
+        if self.screen == Screen::Lobby {
+            self.update_lobby(ctx, keys);
+            return;
+        }
+
+        if self.screen == Screen::GameOver {
+            self.update_game_over(ctx, keys);
+            return;
+        }
+
+        if self.online.is_some() {
+            // Menus and course changes would pull this side out of step with the opponent.
+            keys.retain(|key| key == "w" || key == "o");
+
+            if self.update_online(ctx, &keys) {
+                return;
+            }
+        } else if keys.iter().any(|key| key == "o") {
+            self.open_lobby(ctx);
+            return;
+        }
+
+        if keys.iter().any(|key| key == "n") {
+            self.open_mode_select(ctx);
+            return;
+        }
+
+        if keys.iter().any(|key| key == "c") {
+            self.open_code_entry(ctx);
+            return;
+        }
+
+        if keys.iter().any(|key| key == "g") {
+            self.share_code();
+        }
+
+        if keys.iter().any(|key| key == "m") {
+            self.open_editor(ctx);
+            return;
+        }
+
+        if keys.iter().any(|key| key == "s") {
+            self.open_shop(ctx);
+            return;
+        }
+
+        if keys.iter().any(|key| key == "l") {
+            self.cycle_course(ctx);
+        }
+
+        if self.sim.mode.kind() == ModeKind::Practice && self.update_practice(ctx, &keys) {
+            return;
+        }
+
+        let flapped = keys.iter().any(|key| key == "w");
+        let stepped = match &mut self.online {
+            // `update_online` has already handed this tick's flap to the rollback.
+            Some(online) => online.rollback.advance(&mut self.sim),
+            None => {
+                let rival_flapped = keys.iter().any(|key| key == RIVAL_KEY);
+                self.sim.step([flapped, rival_flapped]).map(Some)
+            }
+        };
+
+        self.play_tick(ctx, stepped, flapped);
     }
-    
-    fn check_collisions(&mut self, ctx: &mut Context) {
-        let bird_target = Target::ByName("flappybird".to_string());
-        let obstacle_target = Target::ByTag("obstacle".to_string());
-        
-        if self.canvas.collision_between(&bird_target, &obstacle_target) {
-            self.game_over = true;
-            self.handle_game_over(ctx);
+
+    /// Brings the canvas in line with the tick just played, or ends the run if it couldn't be.
+    fn play_tick(&mut self, ctx: &mut Context, stepped: Result<Option<StepEvents>, Ending>, flapped: bool) {
+        let events = match stepped {
+            Ok(Some(events)) => events,
+            // The round ended on a guess at the opponent's flaps; hold until they confirm it.
+            Ok(None) => return,
+            Err(ending) => {
+                self.end_run(ctx, ending);
+                return;
+            }
+        };
+        self.replay.record(flapped);
+
+        for (player, ending) in events.eliminated {
+            self.eliminate(player, ending);
+        }
+        if events.scored[0] > 0 {
+            println!("Score: {}", self.sim.score());
         }
+
+        self.sim.pipes.draw_step(ctx, &mut self.canvas, &events.pipes);
+        self.pickup_sprites.sync(ctx, &mut self.canvas, self.sim.pickups());
+        self.draw_run(ctx);
+
+        if self.sim.mode.kind() == ModeKind::Practice {
+            self.practice.record(self.sim.snapshot());
+        }
+
+        self.autosave_run();
     }
-    
-    fn check_ceiling_collision(&mut self) {
-        if let Some(bird) = self.canvas.get_game_object_mut("flappybird") {
-            if bird.position.1 <= 0.0 {
-                bird.position.1 = 0.0;
-                bird.momentum.1 = 0.0;
+
+    /// Draws the birds, the scenery and the HUD where the simulation has them.
+    fn draw_run(&mut self, ctx: &mut Context) {
+        self.draw_birds(ctx);
+        self.sim.powerups.update_display(ctx, &mut self.canvas);
+        self.update_scrollers();
+        self.sim.player.score.update_display(ctx, &mut self.canvas);
+
+        if let (Some(versus), Some(rival)) = (&self.versus, &self.sim.rival) {
+            let right_x = self.canvas_size.0 - 20.0;
+            versus.draw(ctx, &mut self.canvas, &self.sim.player.score, rival.score.score, right_x);
+        }
+
+        self.draw_mode_hud(ctx);
+        self.draw_course_progress(ctx);
+    }
+
+    /// Puts each bird where the simulation has it, parking any that is out of a versus round.
+    fn draw_birds(&mut self, ctx: &mut Context) {
+        let x = self.sim.bird_x();
+        let tick = self.sim.physics().tick;
+        let player = &self.sim.player;
+        let position = if player.out { bird::PARKED } else { (x, player.bird.y) };
+
+        self.bird.set_scale(self.sim.powerups.bird_scale());
+        self.bird.update(ctx, &mut self.canvas, position, player.bird.momentum, tick);
+
+        if let (Some(versus), Some(rival)) = (&mut self.versus, &self.sim.rival) {
+            // A late flap from an online opponent can undo a crash that was only predicted.
+            if !rival.out && versus.rival.is_dead() {
+                versus.rival.reset();
             }
+
+            let position = if rival.out { bird::PARKED } else { (x, rival.bird.y) };
+            versus.rival.update(ctx, &mut self.canvas, position, rival.bird.momentum, tick);
         }
     }
-    
-    fn handle_game_over(&mut self, ctx: &mut Context) {
-        println!("> GAME OVER!! Score: {}", self.score_manager.score);
-        
-        if let Some(bird) = self.canvas.get_game_object_mut("flappybird") {
-            bird.position = (200.0, 300.0);
-            bird.momentum = (0.0, 0.0);
+
+    fn end_run(&mut self, ctx: &mut Context, ending: Ending) {
+        match ending {
+            Ending::CourseComplete => self.complete_course(ctx),
+            _ if self.sim.rival.is_some() => self.finish_versus_round(ctx),
+            Ending::TimeUp => self.handle_game_over(ctx, ending),
+            _ if self.sim.mode.kind() == ModeKind::Practice => {
+                self.practice.crashed = true;
+                self.bird.kill();
+                self.draw_birds(ctx);
+                println!("> Crashed: hold \"r\" to rewind, \"e\" to restart from the last checkpoint");
+            }
+            _ => self.handle_game_over(ctx, ending),
         }
-        
-        self.pipe_manager.reset(&mut self.canvas);
-        self.score_manager.reset(ctx, &mut self.canvas);
-        
-        self.game_over = false;
     }
-    
-    fn update_base_positions(&mut self) {
-        const BASE_SPEED: f32 = -3.0;
-        let total_width = self.base_width * 4.0;
-        
-        if let Some(base1) = self.canvas.get_game_object_mut("base1") {
-            base1.position.0 += BASE_SPEED;
-            if base1.position.0 < -self.base_width {
-                base1.position.0 += total_width;
+
+    /// Keeps the run in storage every second, since the app can be closed or killed at any time.
+    fn autosave_run(&mut self) {
+        self.autosave_ticks += 1;
+        if self.autosave_ticks.is_multiple_of(AUTOSAVE_TICKS) {
+            self.save_run();
+        }
+    }
+
+    /// Keeps the run in progress in storage so the next launch can offer to resume it.
+    fn save_run(&self) {
+        // A versus round can't go on without both players, and a crashed practice run would only
+        // resume into the pipe it hit.
+        if self.testing || self.versus.is_some() || self.screen != Screen::Playing || self.practice.crashed {
+            return;
+        }
+
+        SavedRun::new(self.sim.mode.kind(), self.course, self.sim.snapshot()).save(&self.storage);
+    }
+
+    /// Holds the run saved last session until "e" resumes it or any other key discards it.
+    fn update_resume(&mut self, ctx: &mut Context, keys: Vec<String>) {
+        self.bird.park(&mut self.canvas);
+
+        if keys.is_empty() {
+            return;
+        }
+
+        self.screen = Screen::Playing;
+        self.bird.unpark(&mut self.canvas);
+
+        let Some(saved) = self.saved_run.take() else {
+            return;
+        };
+
+        if keys.iter().any(|key| key == "e") {
+            self.course = saved.course;
+            self.set_mode(ctx, saved.mode);
+            self.restore(ctx, &saved.snapshot);
+            self.replay.mark_incomplete();
+            println!("> Resumed at score {}", self.sim.score());
+        } else {
+            SavedRun::clear(&self.storage);
+            println!("> Discarded the saved run");
+        }
+    }
+
+    /// Rewinds while "r" is held and restarts from the last checkpoint on "e" after a crash.
+    /// Returns whether the tick was used up.
+    fn update_practice(&mut self, ctx: &mut Context, keys: &[String]) -> bool {
+        if keys.iter().any(|key| key == "r") {
+            if let Some(snapshot) = self.practice.rewind() {
+                self.restore(ctx, &snapshot);
             }
+            return true;
         }
-        
-        if let Some(base2) = self.canvas.get_game_object_mut("base2") {
-            base2.position.0 += BASE_SPEED;
-            if base2.position.0 < -self.base_width {
-                base2.position.0 += total_width;
+
+        if !self.practice.crashed {
+            return false;
+        }
+
+        if keys.iter().any(|key| key == "e") {
+            match self.practice.checkpoint() {
+                Some(snapshot) => self.restore(ctx, &snapshot),
+                None => self.restart_run(ctx),
             }
+            return false;
         }
-        
-        if let Some(base3) = self.canvas.get_game_object_mut("base3") {
-            base3.position.0 += BASE_SPEED;
-            if base3.position.0 < -self.base_width {
-                base3.position.0 += total_width;
+
+        // The simulation holds still, and the bird with it, until the player picks a way back.
+        true
+    }
+
+    /// Puts the run back as it was in `snapshot` and redraws it; the mode and course must be the
+    /// ones it was taken in.
+    fn restore(&mut self, ctx: &mut Context, snapshot: &GameSnapshot) {
+        self.sim.pipes.clear(&mut self.canvas);
+        self.pickup_sprites.clear(&mut self.canvas);
+
+        self.sim.restore(snapshot);
+        self.bird.reset();
+
+        self.sim.pipes.draw_all(ctx, &mut self.canvas);
+        self.pickup_sprites.sync(ctx, &mut self.canvas, self.sim.pickups());
+        self.draw_birds(ctx);
+    }
+
+    /// A bird is out of a versus round that the other is still flying.
+    fn eliminate(&mut self, player: Player, ending: Ending) {
+        match player {
+            Player::One => {
+                self.bird.kill();
+                println!("> Player 1 is out ({}) with {}", ending.name(), self.sim.score());
+            }
+            Player::Two => {
+                if let Some(versus) = &mut self.versus {
+                    versus.rival.kill();
+                }
+                let score = self.sim.rival.as_ref().map_or(0, |rival| rival.score.score);
+                println!("> Player 2 is out ({}) with {}", ending.name(), score);
             }
         }
-        
-        if let Some(base4) = self.canvas.get_game_object_mut("base4") {
-            base4.position.0 += BASE_SPEED;
-            if base4.position.0 < -self.base_width {
-                base4.position.0 += total_width;
+    }
+
+    /// Announces the winner once both birds are out, then starts the next round.
+    fn finish_versus_round(&mut self, ctx: &mut Context) {
+        let player_two = self.sim.rival.as_ref().map_or(0, |rival| rival.score.score);
+        let player_one = self.sim.score();
+
+        match self.sim.winner() {
+            Some(Player::One) => println!("> PLAYER 1 WINS!! {} - {}", player_one, player_two),
+            Some(Player::Two) => println!("> PLAYER 2 WINS!! {} - {}", player_one, player_two),
+            None => println!("> DRAW!! {} - {}", player_one, player_two),
+        }
+
+        if let Some(online) = &mut self.online {
+            online.finish_round();
+            println!("> Waiting for the opponent");
+        }
+
+        self.restart_run(ctx);
+    }
+
+    fn open_lobby(&mut self, ctx: &mut Context) {
+        self.restart_run(ctx);
+        self.screen = Screen::Lobby;
+        self.address_input = None;
+        println!("> Online versus: \"h\" hosts on port {}, \"j\" joins a host, \"o\" leaves", DEFAULT_PORT);
+    }
+
+    fn update_lobby(&mut self, ctx: &mut Context, keys: Vec<String>) {
+        self.bird.park(&mut self.canvas);
+
+        self.handle_net_events(ctx);
+
+        for key in keys {
+            if self.screen != Screen::Lobby {
+                return;
+            }
+
+            if let Some(mut address) = self.address_input.take() {
+                match key.as_str() {
+                    "u" if address.is_empty() => {
+                        println!("> Cancelled");
+                        continue;
+                    }
+                    "u" => {
+                        address.pop();
+                    }
+                    "e" => {
+                        self.join_online(&address);
+                        continue;
+                    }
+                    "-" => address.push('.'),
+                    "=" => address.push(':'),
+                    other => address.push_str(other),
+                }
+
+                println!("> Address: {}", address);
+                self.address_input = Some(address);
+                continue;
+            }
+
+            match key.as_str() {
+                "h" => self.host_online(),
+                "j" if self.online.is_none() => {
+                    self.address_input = Some(String::new());
+                    println!(
+                        "> Type the host's address, \"-\" for \".\" and \"=\" for \":\", then \"e\" to connect (empty joins 127.0.0.1:{}), \"u\" deletes",
+                        DEFAULT_PORT,
+                    );
+                }
+                "o" => {
+                    self.online = None;
+                    self.screen = Screen::Playing;
+                    self.bird.unpark(&mut self.canvas);
+                    println!("> Left the lobby");
+                }
+                _ => {}
             }
         }
     }
-}
 
-pub struct MyApp;
+    fn host_online(&mut self) {
+        if self.online.is_some() {
+            return;
+        }
 
-impl MyApp {
-    fn new(ctx: &mut Context) -> impl Drawable {
-        let flappybird_width = 50.0;
-        let flappybird_height = 35.0;
-        let initial_size = (800.0, 600.0);
-        let pipe_width = 100.0 * 0.5;
-        let pipe_height = 800.0;
-        let gap_size = 220.0;
-        let base_height = 112.0;
-        let base_width = 336.0;
+        let seed = SeededRng::from_entropy().seed as u32;
+        match NetSession::host(DEFAULT_PORT, GeneratorKind::Classic, seed) {
+            Ok(session) => {
+                self.online = Some(Online::new(session));
+                println!("> Hosting on port {}, waiting for an opponent", DEFAULT_PORT);
+            }
+            Err(e) => println!("> {}", e),
+        }
+    }
 
-        let bg_bytes = include_bytes!("../assets/bg.png");
-        let bg_img = image::load_from_memory(bg_bytes)
-            .expect("Failed to load background image");
-        let bg_image = bg_img.to_rgba8();
-        let background_image = Image {
-            shape: ShapeType::Rectangle(0.0, initial_size, 0.0),
-            image: bg_image.into(),
-            color: None
+    fn join_online(&mut self, address: &str) {
+        let address = match address {
+            "" => format!("127.0.0.1:{}", DEFAULT_PORT),
+            address if !address.contains(':') => format!("{}:{}", address, DEFAULT_PORT),
+            address => address.to_string(),
         };
 
-        let flappybird_gif_bytes = include_bytes!("../assets/flappybird.gif");
-        let flappybird_animation = AnimatedSprite::new(
-            flappybird_gif_bytes,
-            (flappybird_width, flappybird_height),
-            12.0  
-        ).expect("Failed to load flappy bird animation");
+        match NetSession::join(&address) {
+            Ok(session) => {
+                self.online = Some(Online::new(session));
+                println!("> Connecting to {}", address);
+            }
+            Err(e) => println!("> {}", e),
+        }
+    }
 
-        let flappybird_image = flappybird_animation.get_current_image();
+    /// Sends this tick's flap and holds both birds while waiting for the next round.
+    /// Returns whether the tick was used up.
+    fn update_online(&mut self, ctx: &mut Context, keys: &[String]) -> bool {
+        if keys.iter().any(|key| key == "o") {
+            self.leave_online(ctx);
+            return true;
+        }
 
-        let base_bytes = include_bytes!("../assets/base.png");
-        let base_img = image::load_from_memory(base_bytes)
-            .expect("Failed to load base image");
-        let base_image = base_img.to_rgba8();
+        self.handle_net_events(ctx);
 
-        let mut stork_canvas = Canvas::new(ctx, initial_size);
+        let Some(online) = &mut self.online else {
+            return true;
+        };
 
-        let background = GameObject::new(
-            ctx,
-            "background".to_string(),
-            background_image,
-            initial_size.0.max(initial_size.1), 
-            (0.0, 0.0), 
-            vec!["background".to_string()],
-            (0.0, 0.0),
-            (1.0, 1.0), 
-            0.0,
-        );
+        if online.waiting {
+            if !online.try_start_round() {
+                self.bird.unpark(&mut self.canvas);
+                if let Some(versus) = &self.versus {
+                    versus.hold(&mut self.canvas);
+                }
+                return true;
+            }
+            println!("> Round {}", online.round);
+        }
 
-        stork_canvas.add_game_object("background".to_string(), background);
-        
-        let flappybird = GameObject::new(
-            ctx,
-            "flappybird".to_string(),
-            flappybird_image,
-            flappybird_width.max(flappybird_height),
-            (200.0, 300.0),
+        online.send_inputs(!self.sim.player.out && keys.iter().any(|key| key == "w"));
+        false
+    }
+
+    fn handle_net_events(&mut self, ctx: &mut Context) {
+        let Some(online) = &self.online else {
+            return;
+        };
+
+        for event in online.session.poll() {
+            match event {
+                NetEvent::Connected { generator, seed } => {
+                    println!("> Opponent connected");
+                    self.screen = Screen::Playing;
+                    self.course = Course::Seeded { generator, seed };
+                    self.set_mode(ctx, ModeKind::Versus);
+
+                    if let Some(versus) = &mut self.versus {
+                        versus.set_remote(&mut self.canvas, true);
+                        self.sim.add_rival(versus.start_y());
+                    }
+                    self.bird.unpark(&mut self.canvas);
+                    if let Some(online) = &mut self.online {
+                        online.try_start_round();
+                    }
+                }
+                NetEvent::Received(Message::Inputs { round, up_to, flaps }) => {
+                    if let Some(online) = &mut self.online {
+                        online.receive_inputs(round, up_to, flaps);
+                    }
+                }
+                NetEvent::Received(Message::Ready { .. }) => {
+                    if let Some(online) = &mut self.online {
+                        online.remote_ready = true;
+                    }
+                }
+                NetEvent::Received(Message::Hello { .. }) => {}
+                NetEvent::Disconnected(reason) => {
+                    println!("> {}", reason);
+                    self.leave_online(ctx);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Drops the connection and goes back to classic endless play.
+    fn leave_online(&mut self, ctx: &mut Context) {
+        self.online = None;
+        self.screen = Screen::Playing;
+        self.course = Course::Endless;
+        self.set_mode(ctx, ModeKind::Classic);
+
+        self.bird.unpark(&mut self.canvas);
+        println!("> Left online play");
+    }
+
+    /// Brings in player two, on a different bird skin from player one.
+    fn start_versus(&mut self, ctx: &mut Context) {
+        let skin = BirdSkin::ALL.into_iter()
+            .find(|skin| *skin != self.cosmetics.equipped.bird)
+            .unwrap_or(BirdSkin::Classic);
+
+        let rival = Bird::new(
+            RIVAL_NAME,
+            skin.animator(&self.atlas),
+            (50.0, 35.0),
             vec![
                 "player".to_string(),
                 "flyingbird".to_string(),
             ],
-            (0.0, 0.0),
-            (0.85, 0.85), 
-            0.30,
-        )
-        .with_animation(flappybird_animation);
+        );
+
+        let mut versus = Versus::new(rival, skin);
+        versus.spawn(ctx, &mut self.canvas);
+        self.sim.add_rival(versus.start_y());
+        self.versus = Some(versus);
+    }
+    
+    fn handle_game_over(&mut self, ctx: &mut Context, ending: Ending) {
+        match ending {
+            Ending::TimeUp => println!("> TIME UP!! Score: {}", self.sim.score()),
+            _ => println!("> GAME OVER!! Score: {}", self.sim.score()),
+        }
+        self.bird.kill();
+        self.draw_birds(ctx);
 
-        stork_canvas.add_game_object("flappybird".to_string(), flappybird);
+        self.screen = Screen::GameOver;
+        // The run is over, so there is nothing to resume if the app closes on this screen.
+        SavedRun::clear(&self.storage);
+        println!("> Press any key to play again");
+    }
 
-        let base_y = initial_size.1 - base_height;
+    /// Holds the dead bird where it crashed until a key is pressed, then starts over.
+    fn update_game_over(&mut self, ctx: &mut Context, keys: Vec<String>) {
+        self.draw_birds(ctx);
+
+        if keys.is_empty() {
+            return;
+        }
+
+        self.screen = Screen::Playing;
+        self.restart_run(ctx);
+
+        if self.testing {
+            self.open_editor(ctx);
+        }
+    }
+
+    fn restart_run(&mut self, ctx: &mut Context) {
+        self.record_run(ctx);
+        self.replay.reset();
+        self.wallet.deposit(self.sim.player.score.coins as u64);
+        self.wallet.save(&self.storage);
         
-        let base1_img_obj = Image {
-            shape: ShapeType::Rectangle(0.0, (base_width, base_height), 0.0),
-            image: base_image.clone().into(),
-            color: None
-        };
+        self.bird.unpark(&mut self.canvas);
+        self.bird.reset();
         
-        let base2_img_obj = Image {
-            shape: ShapeType::Rectangle(0.0, (base_width, base_height), 0.0),
-            image: base_image.clone().into(),
+        self.sim.pipes.clear(&mut self.canvas);
+        self.pickup_sprites.clear(&mut self.canvas);
+        self.sim.powerups.clear_display(&mut self.canvas);
+        self.sim.reset();
+        self.sim.player.score.reset(ctx, &mut self.canvas);
+        self.practice.reset();
+        if let Some(versus) = &mut self.versus {
+            versus.reset(&mut self.canvas);
+        }
+        self.autosave_ticks = 0;
+        SavedRun::clear(&self.storage);
+    }
+
+    /// Switches the rules and re-applies them to the current course.
+    fn set_mode(&mut self, ctx: &mut Context, mode: ModeKind) {
+        // End the current run first so it is filed under the mode it was played in.
+        self.restart_run(ctx);
+        self.clear_mode_hud();
+
+        self.sim.set_mode(mode);
+
+        if mode != ModeKind::Versus {
+            if let Some(mut versus) = self.versus.take() {
+                versus.despawn(&mut self.canvas);
+            }
+            self.sim.remove_rival();
+        } else if self.versus.is_none() {
+            self.start_versus(ctx);
+            println!("> Player 1 flaps with \"w\", player 2 with \"{}\"", RIVAL_KEY);
+        }
+
+        println!("> Mode: {:?}, best: {}", mode, self.records.best(mode));
+        self.set_course(ctx, self.course);
+    }
+
+    /// Shows the mode's own number, such as seconds left, under the coin count.
+    fn draw_mode_hud(&mut self, ctx: &mut Context) {
+        let Some(value) = self.sim.mode.hud_value() else {
+            return;
+        };
+
+        let right_x = self.canvas_size.0 - 20.0;
+        let icon_size = 20.0;
+        let width = self.sim.player.score.draw_number(ctx, &mut self.canvas, "mode_digit", value, (right_x, 104.0), DigitStyle::SMALL);
+
+        let icon_obj = Image {
+            shape: ShapeType::Rectangle(0.0, (icon_size, icon_size), 0.0),
+            image: self.sim.mode.kind().icon(icon_size as u32).into(),
             color: None
         };
 
-        let base1 = GameObject::new(
+        let icon = GameObject::new_rect(
             ctx,
-            "base1".to_string(),
-            base1_img_obj,
-            base_width.max(base_height),
-            (0.0, base_y),
-            vec!["ground".to_string(), "obstacle".to_string()],
+            "mode_icon".to_string(),
+            icon_obj,
+            (icon_size, icon_size),
+            (right_x - width - icon_size - 6.0, 106.0),
+            vec!["score".to_string()],
             (0.0, 0.0),
             (1.0, 1.0),
             0.0,
         );
 
-        let base2 = GameObject::new(
+        self.canvas.remove_game_object("mode_icon");
+        self.canvas.add_game_object("mode_icon".to_string(), icon);
+    }
+
+    fn clear_mode_hud(&mut self) {
+        for idx in 0..10 {
+            self.canvas.remove_game_object(&format!("mode_digit_{}", idx));
+        }
+        self.canvas.remove_game_object("mode_icon");
+    }
+
+    fn open_mode_select(&mut self, ctx: &mut Context) {
+        self.restart_run(ctx);
+        self.screen = Screen::ModeSelect;
+        self.mode_select.open_on(self.sim.mode.kind());
+        self.mode_select.draw(ctx, &mut self.canvas, &self.records, &self.sim.player.score);
+    }
+
+    fn update_mode_select(&mut self, ctx: &mut Context, keys: Vec<String>) {
+        self.bird.park(&mut self.canvas);
+
+        if keys.is_empty() {
+            return;
+        }
+
+        for key in keys {
+            let picked = self.mode_select.handle_key(&key);
+            if key != "n" && picked.is_none() {
+                continue;
+            }
+
+            self.mode_select.clear(&mut self.canvas);
+            self.screen = Screen::Playing;
+
+            self.bird.unpark(&mut self.canvas);
+
+            if let Some(mode) = picked {
+                self.set_mode(ctx, mode);
+            }
+            return;
+        }
+
+        self.mode_select.draw(ctx, &mut self.canvas, &self.records, &self.sim.player.score);
+    }
+
+    /// Steps from endless play to today's daily course, through the embedded levels and back.
+    fn cycle_course(&mut self, ctx: &mut Context) {
+        let next = match self.course {
+            Course::Endless => daily::today().map(Course::Daily).unwrap_or(Course::Level(0)),
+            Course::Daily(_) => Course::Level(0),
+            Course::Level(idx) if idx + 1 < level::EMBEDDED.len() => Course::Level(idx + 1),
+            Course::Level(_) | Course::Seeded { .. } => Course::Endless,
+        };
+
+        self.set_course(ctx, next);
+    }
+
+    fn set_course(&mut self, ctx: &mut Context, course: Course) {
+        self.restart_run(ctx);
+        self.course = course;
+        self.draw_streak(ctx);
+
+        match course {
+            Course::Endless => {
+                println!("> Endless mode, best: {}", self.records.best(self.sim.mode.kind()));
+                self.sim.pipes.set_seed(None);
+                self.sim.pipes.set_generator(self.sim.mode.generator().build());
+            }
+            Course::Daily(day) => {
+                println!(
+                    "> Daily challenge {}, best today: {}, streak: {}",
+                    daily::date_string(day),
+                    self.records.daily_best(day),
+                    self.records.daily_streak(day),
+                );
+                self.sim.pipes.set_seed(Some(daily::seed(day)));
+                self.sim.pipes.set_generator(self.sim.mode.generator().build());
+            }
+            Course::Level(idx) => {
+                let level = Level::embedded(level::EMBEDDED[idx].0).expect("Failed to find embedded level");
+                let solver = SolverConfig::for_canvas(self.canvas_size, self.sim.pipes.pipe_width);
+                if let Err(unreachable) = level.validate(&self.physics, &solver, self.canvas_size.0) {
+                    println!("> Level '{}' may be impossible: pair {} can't be reached", level.name, unreachable.pair_index);
+                }
+
+                println!("> Level: {}, {} pairs", level.name, level.obstacles.len());
+                self.sim.pipes.set_seed(None);
+                self.sim.pipes.set_generator(Box::new(level.course()));
+            }
+            Course::Seeded { generator, seed } => {
+                let challenge = Challenge { mode: self.sim.mode.kind(), course: ChallengeCourse::Seeded { generator, seed } };
+                println!("> Challenge {}", challenge.encode());
+                self.sim.pipes.set_seed(Some(seed as u64));
+                self.sim.pipes.set_generator(generator.build());
+            }
+        }
+    }
+
+    /// Prints a code that replays the current course.
+    fn share_code(&self) {
+        let course = match self.course {
+            Course::Endless => ChallengeCourse::Seeded { generator: self.sim.mode.generator(), seed: self.sim.pipes.seed() as u32 },
+            Course::Seeded { generator, seed } => ChallengeCourse::Seeded { generator, seed },
+            Course::Daily(day) => ChallengeCourse::Daily { day: day as u32 },
+            Course::Level(_) => {
+                println!("> Levels can't be shared as challenge codes");
+                return;
+            }
+        };
+        let challenge = Challenge { mode: self.sim.mode.kind(), course };
+
+        println!("> Challenge code: {}", challenge.encode());
+    }
+
+    fn open_code_entry(&mut self, ctx: &mut Context) {
+        self.restart_run(ctx);
+        self.screen = Screen::CodeEntry;
+        self.code_input.clear();
+        println!("> Type a challenge code, \"u\" deletes a character or cancels when empty");
+    }
+
+    fn update_code_entry(&mut self, ctx: &mut Context, keys: Vec<String>) {
+        self.bird.park(&mut self.canvas);
+
+        for key in keys {
+            if key == "u" {
+                if self.code_input.pop().is_none() {
+                    println!("> Cancelled");
+                    self.close_code_entry();
+                    return;
+                }
+            } else if key.chars().all(|c| c.is_ascii_alphanumeric()) {
+                self.code_input.push_str(&key.to_ascii_uppercase());
+            }
+
+            println!("> Code: {}", self.code_input);
+
+            if self.code_input.len() < CODE_LENGTH {
+                continue;
+            }
+
+            match Challenge::parse(&self.code_input) {
+                Ok(challenge) => {
+                    self.close_code_entry();
+                    self.course = match challenge.course {
+                        ChallengeCourse::Seeded { generator, seed } => Course::Seeded { generator, seed },
+                        ChallengeCourse::Daily { day } => Course::Daily(day as i64),
+                    };
+                    self.set_mode(ctx, challenge.mode);
+                }
+                Err(e) => {
+                    println!("> {}", e);
+                    self.code_input.clear();
+                }
+            }
+            return;
+        }
+    }
+
+    fn close_code_entry(&mut self) {
+        self.code_input.clear();
+        self.screen = Screen::Playing;
+
+        self.bird.unpark(&mut self.canvas);
+    }
+
+    /// Shows how many pairs an authored course has left to send, next to a finish flag.
+    fn draw_course_progress(&mut self, ctx: &mut Context) {
+        for idx in 0..10 {
+            self.canvas.remove_game_object(&format!("course_digit_{}", idx));
+        }
+        self.canvas.remove_game_object("course_icon");
+
+        let Some(remaining) = self.sim.pipes.generator().remaining() else {
+            return;
+        };
+
+        let icon_size = 24.0;
+        let digits_width = DigitStyle::SMALL.text_width(remaining);
+        self.sim.player.score.draw_number(ctx, &mut self.canvas, "course_digit", remaining, (20.0 + icon_size + 6.0 + digits_width, 22.0), DigitStyle::SMALL);
+
+        let icon_obj = Image {
+            shape: ShapeType::Rectangle(0.0, (icon_size, icon_size), 0.0),
+            image: finish_line_image(icon_size as u32, icon_size as u32).into(),
+            color: None
+        };
+
+        let icon = GameObject::new_rect(
             ctx,
-            "base2".to_string(),
-            base2_img_obj,
-            base_width.max(base_height),
-            (base_width, base_y),
-            vec!["ground".to_string(), "obstacle".to_string()],
+            "course_icon".to_string(),
+            icon_obj,
+            (icon_size, icon_size),
+            (20.0, 22.0),
+            vec!["score".to_string()],
             (0.0, 0.0),
             (1.0, 1.0),
             0.0,
         );
-        
-        let base3_img_obj = Image {
-            shape: ShapeType::Rectangle(0.0, (base_width, base_height), 0.0),
-            image: base_image.clone().into(),
+
+        self.canvas.add_game_object("course_icon".to_string(), icon);
+    }
+
+    /// Shows the daily streak in the top left while the daily course is up, below the power-up
+    /// indicators and the course progress.
+    fn draw_streak(&mut self, ctx: &mut Context) {
+        for idx in 0..10 {
+            self.canvas.remove_game_object(&format!("streak_digit_{}", idx));
+        }
+        self.canvas.remove_game_object("streak_icon");
+
+        let Course::Daily(day) = self.course else {
+            return;
+        };
+
+        let streak = self.records.daily_streak(day) as usize;
+        let icon_size = 24.0;
+        let digits_width = DigitStyle::SMALL.text_width(streak);
+        self.sim.player.score.draw_number(ctx, &mut self.canvas, "streak_digit", streak, (20.0 + icon_size + 6.0 + digits_width, STREAK_Y), DigitStyle::SMALL);
+
+        let icon_obj = Image {
+            shape: ShapeType::Rectangle(0.0, (icon_size, icon_size), 0.0),
+            image: daily::streak_icon(icon_size as u32).into(),
             color: None
         };
-        
-        let base3 = GameObject::new(
+
+        let icon = GameObject::new_rect(
             ctx,
-            "base3".to_string(),
-            base3_img_obj,
-            base_width.max(base_height),
-            (base_width * 2.0, base_y),
-            vec!["ground".to_string(), "obstacle".to_string()],
+            "streak_icon".to_string(),
+            icon_obj,
+            (icon_size, icon_size),
+            (20.0, STREAK_Y),
+            vec!["score".to_string()],
             (0.0, 0.0),
             (1.0, 1.0),
             0.0,
         );
-        
-        let base4_img_obj = Image {
-            shape: ShapeType::Rectangle(0.0, (base_width, base_height), 0.0),
-            image: base_image.clone().into(),
+
+        self.canvas.add_game_object("streak_icon".to_string(), icon);
+    }
+
+    /// Files the finished run's score under the course it was played on.
+    fn record_run(&mut self, ctx: &mut Context) {
+        let score = self.sim.score();
+        // Practice runs can be rewound and versus scores are shared, so neither counts.
+        if score == 0 || self.testing || matches!(self.sim.mode.kind(), ModeKind::Practice | ModeKind::Versus) {
+            return;
+        }
+
+        let (best, board) = match self.course {
+            Course::Endless => (self.records.record_endless(self.sim.mode.kind(), score), Some(self.sim.mode.kind().name().to_string())),
+            // Only classic runs on today's course count towards the daily.
+            Course::Daily(day) if daily::today() == Some(day) && self.sim.mode.kind() == ModeKind::Classic => {
+                let best = self.records.record_daily(day, score);
+                self.draw_streak(ctx);
+                (best, Some(format!("daily-{}", daily::date_string(day))))
+            }
+            Course::Daily(_) | Course::Level(_) | Course::Seeded { .. } => (false, None),
+        };
+
+        if best {
+            println!("> New best: {}", score);
+        }
+        self.records.save(&self.storage);
+
+        if let Some(day) = daily::today() {
+            RunLog::append(&self.storage, RunEntry { day, mode: self.sim.mode.kind(), score, ticks: self.replay.ticks() });
+        }
+
+        let replay = self.replay.finish(self.sim.mode.kind(), self.sim.mode.generator(), self.sim.pipes.seed());
+        if let (Some(board), Some(replay)) = (board, replay) {
+            self.leaderboard.submit(Submission { board, player: self.profile.name.clone(), score, replay });
+        }
+    }
+
+    fn print_standings(&self, standings: &Standings) {
+        match &standings.player {
+            Some(entry) => println!("> Leaderboard {}: #{} of {}", standings.board, entry.rank, standings.total),
+            None => println!("> Leaderboard {}", standings.board),
+        }
+
+        for entry in &standings.top {
+            let you = if entry.player == self.profile.name { " (you)" } else { "" };
+            println!(">   {}. {} {}{}", entry.rank, entry.player, entry.score, you);
+        }
+    }
+
+    fn complete_course(&mut self, ctx: &mut Context) {
+        println!("> COURSE COMPLETE!! Score: {}", self.sim.score());
+
+        self.restart_run(ctx);
+
+        if self.testing {
+            self.open_editor(ctx);
+            return;
+        }
+
+        self.screen = Screen::CourseComplete;
+
+        let banner_size = (240.0, 120.0);
+        let banner_image = Image {
+            shape: ShapeType::Rectangle(0.0, banner_size, 0.0),
+            image: finish_line_image(banner_size.0 as u32, banner_size.1 as u32).into(),
             color: None
         };
-        
-        let base4 = GameObject::new(
+
+        let banner = GameObject::new_rect(
             ctx,
-            "base4".to_string(),
-            base4_img_obj,
-            base_width.max(base_height),
-            (base_width * 3.0, base_y),
-            vec!["ground".to_string(), "obstacle".to_string()],
+            "course_complete".to_string(),
+            banner_image,
+            banner_size,
+            ((self.canvas_size.0 - banner_size.0) / 2.0, (self.canvas_size.1 - banner_size.1) / 2.0 - 60.0),
+            vec!["ui".to_string()],
             (0.0, 0.0),
             (1.0, 1.0),
             0.0,
         );
 
-        stork_canvas.add_game_object("base1".to_string(), base1);
-        stork_canvas.add_game_object("base2".to_string(), base2);
-        stork_canvas.add_game_object("base3".to_string(), base3);
-        stork_canvas.add_game_object("base4".to_string(), base4);
-
-        stork_canvas.add_event(
-            GameEvent::KeyPress {
-                key: Key::Character("w".to_string().into()),
-                action: Action::ApplyMomentum {  
-                    target: Target::ById("flappybird".to_string()),
-                    value: (0.0, -10.5)  
+        self.canvas.add_game_object("course_complete".to_string(), banner);
+    }
+
+    /// Holds the finish banner until "e" starts the course again.
+    fn update_course_complete(&mut self, keys: Vec<String>) {
+        self.bird.park(&mut self.canvas);
+
+        if keys.iter().any(|key| key == "e") {
+            self.canvas.remove_game_object("course_complete");
+            self.screen = Screen::Playing;
+
+            self.bird.unpark(&mut self.canvas);
+        }
+    }
+
+    /// Opens the editor on the level being test-played, the saved custom level, or a blank one.
+    fn open_editor(&mut self, ctx: &mut Context) {
+        self.restart_run(ctx);
+        self.testing = false;
+        self.screen = Screen::Editor;
+        self.course = Course::Endless;
+        self.draw_streak(ctx);
+        self.sim.pipes.set_seed(None);
+
+        let pipe_manager = &mut self.sim.pipes;
+        let editor = self.editor.get_or_insert_with(|| {
+            let level = Level::load(EDITOR_LEVEL_PATH)
+                .unwrap_or_else(|_| Level::empty("Custom", pipe_manager.gap_size));
+            Editor::new(level, EDITOR_LEVEL_PATH)
+        });
+
+        pipe_manager.set_generator(Box::new(ClassicGenerator));
+        editor.draw(ctx, &mut self.canvas, pipe_manager, self.canvas_size);
+        self.pointer.arm(ctx, &mut self.canvas, self.canvas_size);
+    }
+
+    fn update_editor(&mut self, ctx: &mut Context, keys: Vec<String>) {
+        self.bird.park(&mut self.canvas);
+
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+
+        let press = self.pointer.poll(&mut self.canvas);
+        if keys.is_empty() && press.is_none() {
+            return;
+        }
+
+        if let Some(position) = press {
+            editor.handle_press(position, self.canvas_size, self.sim.pipes.pipe_width);
+        }
+
+        for key in keys {
+            match editor.handle_key(&key, self.sim.pipes.pipe_width) {
+                EditorAction::None => {}
+                EditorAction::Save => match editor.level.save(&editor.path) {
+                    Ok(()) => println!("> Saved {}", editor.path),
+                    Err(e) => println!("> {}", e),
                 },
-                target: Target::ById("flappybird".to_string())
-            },
-            Target::ById("flappybird".to_string())
+                EditorAction::TestPlay(level) => {
+                    editor.clear(&mut self.canvas);
+                    self.pointer.disarm(&mut self.canvas);
+                    self.screen = Screen::Playing;
+                    self.testing = true;
+                    self.sim.pipes.set_generator(Box::new(level.course()));
+
+                    self.bird.unpark(&mut self.canvas);
+                    return;
+                }
+                EditorAction::Exit => {
+                    editor.clear(&mut self.canvas);
+                    self.pointer.disarm(&mut self.canvas);
+                    self.screen = Screen::Playing;
+
+                    self.bird.unpark(&mut self.canvas);
+                    return;
+                }
+            }
+        }
+
+        editor.draw(ctx, &mut self.canvas, &self.sim.pipes, self.canvas_size);
+    }
+
+    fn open_shop(&mut self, ctx: &mut Context) {
+        self.restart_run(ctx);
+        self.screen = Screen::Shop;
+        self.shop.draw(ctx, &mut self.canvas, &self.wallet, &self.cosmetics, &self.sim.player.score);
+    }
+
+    fn update_shop(&mut self, ctx: &mut Context, keys: Vec<String>) {
+        // Park the bird off screen so it neither falls nor covers the shop.
+        self.bird.park(&mut self.canvas);
+
+        if keys.is_empty() {
+            return;
+        }
+
+        for key in keys {
+            if key == "s" {
+                self.shop.clear(&mut self.canvas);
+                self.screen = Screen::Playing;
+
+                self.bird.unpark(&mut self.canvas);
+                return;
+            }
+
+            if let Some(skin) = self.shop.handle_key(&key, &mut self.wallet, &mut self.cosmetics) {
+                self.apply_skin(ctx, skin);
+                self.wallet.save(&self.storage);
+                self.cosmetics.save(&self.storage);
+            }
+        }
+
+        self.shop.draw(ctx, &mut self.canvas, &self.wallet, &self.cosmetics, &self.sim.player.score);
+    }
+
+    fn apply_skin(&mut self, ctx: &mut Context, skin: Skin) {
+        match skin {
+            Skin::Bird(bird) => {
+                self.bird.set_animator(bird.animator(&self.atlas));
+                self.bird.set_tilt(bird.tilt());
+            }
+            Skin::Pipes(pipes) => self.sim.pipes.set_skin(pipes, &self.atlas),
+            Skin::Background(background) => {
+                // Re-adding keeps the draw order: background first, then the strips.
+                spawn_background(ctx, &mut self.canvas, background, self.canvas_size);
+                for strip in &mut self.scrollers {
+                    strip.spawn(ctx, &mut self.canvas, self.canvas_size.0);
+                }
+            }
+        }
+    }
+    
+    fn update_scrollers(&mut self) {
+        let speed = self.sim.world_speed.value();
+
+        for strip in &mut self.scrollers {
+            strip.update(&mut self.canvas, speed);
+        }
+    }
+}
+
+fn spawn_background(ctx: &mut Context, canvas: &mut Canvas, skin: BackgroundSkin, size: (f32, f32)) {
+    let background_image = Image {
+        shape: ShapeType::Rectangle(0.0, size, 0.0),
+        image: skin.image().into(),
+        color: None
+    };
+
+    let background = GameObject::new(
+        ctx,
+        "background".to_string(),
+        background_image,
+        size.0.max(size.1), 
+        (0.0, 0.0), 
+        vec!["background".to_string()],
+        (0.0, 0.0),
+        (1.0, 1.0), 
+        0.0,
+    );
+
+    canvas.remove_game_object("background");
+    canvas.add_game_object("background".to_string(), background);
+}
+
+pub struct MyApp;
+
+impl MyApp {
+    fn new(ctx: &mut Context) -> impl Drawable {
+        let flappybird_width = 50.0;
+        let flappybird_height = 35.0;
+        let initial_size = sim::CANVAS_SIZE;
+        let pipe_width = sim::PIPE_WIDTH;
+        let pipe_height = sim::PIPE_HEIGHT;
+        let gap_size = sim::GAP_SIZE;
+        let base_height = 112.0;
+        let base_width = 336.0;
+
+        let physics = Physics::default();
+
+        let storage = Storage::open();
+        let wallet = Wallet::load(&storage);
+        let records = Records::load(&storage);
+        let saved_run = SavedRun::load(&storage);
+        let profile = Profile::load_or_create(&storage);
+        let cosmetics = Cosmetics::load(&storage);
+
+        let atlas = Atlas::from_bytes(
+            include_bytes!("../assets/atlas.png"),
+            include_str!("../assets/atlas.json"),
+        ).expect("Failed to load sprite atlas");
+
+        let mut bird = Bird::new(
+            "flappybird",
+            cosmetics.equipped.bird.animator(&atlas),
+            (flappybird_width, flappybird_height),
+            vec![
+                "player".to_string(),
+                "flyingbird".to_string(),
+            ],
+        ).with_tilt(cosmetics.equipped.bird.tilt());
+
+        let base_bytes = include_bytes!("../assets/base.png");
+        let base_img = image::load_from_memory(base_bytes)
+            .expect("Failed to load base image");
+        let base_image = base_img.to_rgba8();
+
+        let mut stork_canvas = Canvas::new(ctx, initial_size);
+
+        spawn_background(ctx, &mut stork_canvas, cosmetics.equipped.background, initial_size);
+
+        let base_y = initial_size.1 - base_height;
+
+        let hills_height = 90.0;
+        let mut hills = ScrollingStrip::new(
+            "hills",
+            scroller::hills_image(base_width as u32, hills_height as u32),
+            (base_width, hills_height),
+            base_y - hills_height,
+            vec!["scenery".to_string()],
+        ).with_parallax(0.4);
+        hills.spawn(ctx, &mut stork_canvas, initial_size.0);
+        
+        bird.spawn(ctx, &mut stork_canvas, sim::BIRD_START);
+
+        let mut ground = ScrollingStrip::new(
+            "base",
+            base_image.clone(),
+            (base_width, base_height),
+            base_y,
+            vec!["ground".to_string(), "obstacle".to_string()],
         );
+        ground.spawn(ctx, &mut stork_canvas, initial_size.0);
+
+        let ceiling_height = 16.0;
+        let mut ceiling = ScrollingStrip::new(
+            "ceiling",
+            scroller::ceiling_image(&base_image, ceiling_height as u32),
+            (base_width, ceiling_height),
+            0.0,
+            vec!["ceiling".to_string()],
+        );
+        ceiling.spawn(ctx, &mut stork_canvas, initial_size.0);
 
         let pipe_manager = PipeManager::new(
             pipe_width,
             pipe_height,
             gap_size,
             initial_size,
-        );
+            cosmetics.equipped.pipes,
+            &atlas,
+        ).with_physics(physics, SolverConfig::for_canvas(initial_size, pipe_width));
 
-        let score_manager = ScoreManager::new(initial_size);
+        let mut sim = Simulation::with_pipes(ModeKind::Classic, pipe_manager, &Tuning::default());
 
-        let mut game = Game { 
-            canvas: stork_canvas,
-            canvas_size: initial_size,
-            pipe_manager,
-            score_manager,
-            base_width,
-            game_over: false,
-        };
+        let digit_names: Vec<String> = (0..10).map(|digit| format!("digit_{}", digit)).collect();
+        if let Some(number_images) = atlas.frames(&digit_names) {
+            sim.player.score = ScoreManager::new(initial_size).with_number_images(number_images);
+        }
 
-        game.canvas.add_event(
-            GameEvent::Collision {
-                action: Action::ApplyMomentum {
-                    target: Target::ById("flappybird".to_string()),
-                    value: (0.0, 0.0)
-                },
-                target: Target::ByTag("obstacle".to_string())
-            },
-            Target::ById("flappybird".to_string())
-        );
+        let mut input = InputProbes::new();
+        // Every letter and digit, so challenge codes can be typed in.
+        let typeable = ('a'..='z').chain('0'..='9').map(String::from);
+        for key in typeable.chain(["-".to_string(), "=".to_string()]) {
+            input.register_char(ctx, &mut stork_canvas, &key);
+        }
 
-        game
+        let screen = match &saved_run {
+            Some(saved) => {
+                println!("> Resume your last {:?} run at score {}? \"e\" resumes, any other key discards it", saved.mode, saved.snapshot.player.score.score);
+                Screen::Resume
+            }
+            None => Screen::Playing,
+        };
+
+        Game { 
+            canvas: stork_canvas,
+            canvas_size: initial_size,
+            bird,
+            sim,
+            pickup_sprites: PickupSprites::new(),
+            wallet,
+            cosmetics,
+            shop: Shop::new(&atlas),
+            atlas,
+            storage,
+            input,
+            pointer: PointerProbes::new(),
+            clock: FixedStep::new(physics.tick),
+            scrollers: vec![hills, ground, ceiling],
+            physics,
+            course: Course::Endless,
+            mode_select: ModeSelect::new(),
+            practice: Practice::new(),
+            versus: None,
+            records,
+            replay: ReplayRecorder::new(),
+            leaderboard: Leaderboard::new(Box::new(TcpLeaderboard::from_env())),
+            profile,
+            editor: None,
+            testing: false,
+            code_input: String::new(),
+            online: None,
+            address_input: None,
+            saved_run,
+            autosave_ticks: 0,
+            screen,
+        }
     }
 }
 
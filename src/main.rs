@@ -1,6 +1,45 @@
+#[cfg(not(target_arch="wasm32"))]
+const USAGE: &str = "Usage:
+  orange [play]      Launch the game
+  orange background  Maintain cache.db and serve stats over a local socket
+  orange simulate    Play a batch of headless runs with a bot and report how they went
+  orange help        Show this message";
+
+#[cfg(not(target_arch="wasm32"))]
+fn exit_with_usage(error: &str, usage: &str) -> ! {
+    println!("> {}", error);
+    println!("{}", usage);
+    std::process::exit(2);
+}
+
 fn main() {
     #[cfg(not(target_arch="wasm32"))]
     {
-        main::maverick_main()
+        let args: Vec<String> = std::env::args().skip(1).collect();
+
+        match args.first().map(String::as_str) {
+            None | Some("play") => main::maverick_main(),
+            Some("background") => {
+                let config = main::background::Config::from_args(&args[1..])
+                    .unwrap_or_else(|e| exit_with_usage(&e, main::background::Config::USAGE));
+
+                if let Err(e) = main::background::run(&config) {
+                    println!("> {}", e);
+                    std::process::exit(1);
+                }
+            }
+            Some("simulate") => {
+                let config = main::simulate::Config::from_args(&args[1..])
+                    .unwrap_or_else(|e| exit_with_usage(&e, main::simulate::Config::USAGE));
+
+                let report = main::simulate::run(&config);
+                match config.format {
+                    main::simulate::Format::Json => println!("{}", report.to_json()),
+                    main::simulate::Format::Csv => print!("{}", report.to_csv()),
+                }
+            }
+            Some("help" | "--help" | "-h") => println!("{}", USAGE),
+            Some(command) => exit_with_usage(&format!("Unknown command {}", command), USAGE),
+        }
     }
 }
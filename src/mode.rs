@@ -0,0 +1,338 @@
+use serde::{Serialize, Deserialize};
+
+use crate::obstacle::GeneratorKind;
+use crate::pipe::MIN_PASSABLE_GAP;
+use crate::ui;
+
+/// What the bird ran into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hit {
+    Pipe,
+    Ground,
+    Ceiling,
+}
+
+/// Whether the run goes on after a tick or a hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Continue,
+    Crashed,
+    TimeUp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ModeKind {
+    Classic,
+    TimeAttack,
+    Zen,
+    Hardcore,
+    Practice,
+    Versus,
+}
+
+impl ModeKind {
+    pub const ALL: [ModeKind; 6] = [
+        ModeKind::Classic,
+        ModeKind::TimeAttack,
+        ModeKind::Zen,
+        ModeKind::Hardcore,
+        ModeKind::Practice,
+        ModeKind::Versus,
+    ];
+
+    pub fn build(&self) -> Box<dyn GameMode> {
+        match self {
+            ModeKind::Classic => Box::new(Classic),
+            ModeKind::TimeAttack => Box::new(TimeAttack::new(60.0)),
+            ModeKind::Zen => Box::new(Zen::default()),
+            ModeKind::Hardcore => Box::new(Hardcore),
+            ModeKind::Practice => Box::new(Practice),
+            ModeKind::Versus => Box::new(Versus),
+        }
+    }
+
+    /// Stable number for the mode, used in challenge codes.
+    pub fn index(&self) -> u8 {
+        match self {
+            ModeKind::Classic => 0,
+            ModeKind::TimeAttack => 1,
+            ModeKind::Zen => 2,
+            ModeKind::Hardcore => 3,
+            ModeKind::Practice => 4,
+            ModeKind::Versus => 5,
+        }
+    }
+
+    pub fn from_index(index: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.index() == index)
+    }
+
+    /// Lowercase name, used for leaderboard boards.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModeKind::Classic => "classic",
+            ModeKind::TimeAttack => "time-attack",
+            ModeKind::Zen => "zen",
+            ModeKind::Hardcore => "hardcore",
+            ModeKind::Practice => "practice",
+            ModeKind::Versus => "versus",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// Badge for the mode select screen and the HUD.
+    pub fn icon(&self, size: u32) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+        let radius = size as f32 / 2.0;
+
+        ui::badge(size, radius * 0.15, |dx, dy| {
+            let line = radius * 0.12;
+            match self {
+                // A bird-yellow disc.
+                ModeKind::Classic => image::Rgba([250, 200, 40, 255]),
+                // A clock face with its hands at twelve and three.
+                ModeKind::TimeAttack => {
+                    let hand = (dx.abs() < line && dy < 0.0 && -dy < radius * 0.6)
+                        || (dy.abs() < line && dx > 0.0 && dx < radius * 0.45);
+                    if hand { image::Rgba([30, 30, 30, 255]) } else { image::Rgba([120, 200, 255, 255]) }
+                }
+                // A calm ring.
+                ModeKind::Zen => {
+                    let dist = (dx * dx + dy * dy).sqrt();
+                    if (dist - radius * 0.45).abs() < line { image::Rgba([255, 255, 255, 255]) } else { image::Rgba([90, 200, 140, 255]) }
+                }
+                // A cross.
+                ModeKind::Hardcore => {
+                    if (dx - dy).abs() < line * 1.5 || (dx + dy).abs() < line * 1.5 {
+                        image::Rgba([30, 30, 30, 255])
+                    } else {
+                        image::Rgba([220, 50, 50, 255])
+                    }
+                }
+                // A flag on a pole.
+                ModeKind::Practice => {
+                    let pole = (dx + radius * 0.25).abs() < line * 0.6 && dy.abs() < radius * 0.5;
+                    let flag = dx > -radius * 0.25 && dx < radius * 0.35 && dy > -radius * 0.5 && dy < -radius * 0.1;
+                    if pole || flag { image::Rgba([255, 255, 255, 255]) } else { image::Rgba([70, 110, 220, 255]) }
+                }
+                // Two birds side by side.
+                ModeKind::Versus => {
+                    let bird = radius * 0.25;
+                    let left = ((dx + radius * 0.3).powi(2) + dy * dy).sqrt() < bird;
+                    let right = ((dx - radius * 0.3).powi(2) + dy * dy).sqrt() < bird;
+                    if left {
+                        image::Rgba([250, 200, 40, 255])
+                    } else if right {
+                        image::Rgba([220, 50, 50, 255])
+                    } else {
+                        image::Rgba([110, 110, 130, 255])
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// The rules of a run: what ends it, how it scores and how hard the pipes are.
+pub trait GameMode: std::fmt::Debug {
+    fn kind(&self) -> ModeKind;
+
+    /// Decides what a hit does once shields have had their say. `new_contact` is false while
+    /// the bird stays inside whatever it hit.
+    fn on_hit(&mut self, hit: Hit, new_contact: bool) -> Verdict;
+
+    /// Called every tick with the seconds that passed; modes with a clock end the run here.
+    fn update(&mut self, _dt: f32) -> Verdict {
+        Verdict::Continue
+    }
+
+    fn gap_size(&self, base: f32) -> f32 {
+        base
+    }
+
+    /// Generator for endless and daily courses.
+    fn generator(&self) -> GeneratorKind {
+        GeneratorKind::Classic
+    }
+
+    /// Whether touching the top of the screen counts as a hit.
+    fn deadly_ceiling(&self) -> bool {
+        false
+    }
+
+    /// A number shown under the score, such as seconds left or collisions.
+    fn hud_value(&self) -> Option<usize> {
+        None
+    }
+
+    /// Mode-specific progress, such as the clock, for snapshots.
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// Picks up from a state returned by `save_state`.
+    fn load_state(&mut self, _state: &serde_json::Value) {}
+
+    fn reset(&mut self);
+}
+
+/// Today's rules: any hit ends the run.
+#[derive(Debug, Clone, Default)]
+pub struct Classic;
+
+impl GameMode for Classic {
+    fn kind(&self) -> ModeKind {
+        ModeKind::Classic
+    }
+
+    fn on_hit(&mut self, hit: Hit, _new_contact: bool) -> Verdict {
+        match hit {
+            Hit::Pipe | Hit::Ground => Verdict::Crashed,
+            Hit::Ceiling => Verdict::Continue,
+        }
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// As many pipes as possible before the clock runs out.
+#[derive(Debug, Clone)]
+pub struct TimeAttack {
+    pub duration: f32,
+    remaining: f32,
+}
+
+impl TimeAttack {
+    pub fn new(duration: f32) -> Self {
+        Self { duration, remaining: duration }
+    }
+}
+
+impl GameMode for TimeAttack {
+    fn kind(&self) -> ModeKind {
+        ModeKind::TimeAttack
+    }
+
+    fn on_hit(&mut self, hit: Hit, new_contact: bool) -> Verdict {
+        Classic.on_hit(hit, new_contact)
+    }
+
+    fn update(&mut self, dt: f32) -> Verdict {
+        self.remaining = (self.remaining - dt).max(0.0);
+        if self.remaining <= 0.0 { Verdict::TimeUp } else { Verdict::Continue }
+    }
+
+    fn hud_value(&self) -> Option<usize> {
+        Some(self.remaining.ceil() as usize)
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::json!(self.remaining)
+    }
+
+    fn load_state(&mut self, state: &serde_json::Value) {
+        self.remaining = state.as_f64().map(|remaining| remaining as f32).unwrap_or(self.duration);
+    }
+
+    fn reset(&mut self) {
+        self.remaining = self.duration;
+    }
+}
+
+/// No death; pipes are passed through and every bump is counted.
+#[derive(Debug, Clone, Default)]
+pub struct Zen {
+    pub collisions: usize,
+}
+
+impl GameMode for Zen {
+    fn kind(&self) -> ModeKind {
+        ModeKind::Zen
+    }
+
+    fn on_hit(&mut self, hit: Hit, new_contact: bool) -> Verdict {
+        if hit == Hit::Pipe && new_contact {
+            self.collisions += 1;
+        }
+
+        Verdict::Continue
+    }
+
+    fn generator(&self) -> GeneratorKind {
+        GeneratorKind::SineCorridor
+    }
+
+    fn hud_value(&self) -> Option<usize> {
+        Some(self.collisions)
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::json!(self.collisions)
+    }
+
+    fn load_state(&mut self, state: &serde_json::Value) {
+        self.collisions = state.as_u64().unwrap_or(0) as usize;
+    }
+
+    fn reset(&mut self) {
+        self.collisions = 0;
+    }
+}
+
+/// Narrower gaps and a deadly ceiling.
+#[derive(Debug, Clone, Default)]
+pub struct Hardcore;
+
+impl GameMode for Hardcore {
+    fn kind(&self) -> ModeKind {
+        ModeKind::Hardcore
+    }
+
+    fn on_hit(&mut self, _hit: Hit, _new_contact: bool) -> Verdict {
+        Verdict::Crashed
+    }
+
+    fn gap_size(&self, base: f32) -> f32 {
+        (base * 0.8).max(MIN_PASSABLE_GAP)
+    }
+
+    fn deadly_ceiling(&self) -> bool {
+        true
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Classic rules, but a crash pauses the run so it can be rewound or restarted from a checkpoint.
+#[derive(Debug, Clone, Default)]
+pub struct Practice;
+
+impl GameMode for Practice {
+    fn kind(&self) -> ModeKind {
+        ModeKind::Practice
+    }
+
+    fn on_hit(&mut self, hit: Hit, new_contact: bool) -> Verdict {
+        Classic.on_hit(hit, new_contact)
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Two birds on one course; a bird that crashes is out and the round ends once both are.
+#[derive(Debug, Clone, Default)]
+pub struct Versus;
+
+impl GameMode for Versus {
+    fn kind(&self) -> ModeKind {
+        ModeKind::Versus
+    }
+
+    fn on_hit(&mut self, hit: Hit, new_contact: bool) -> Verdict {
+        Classic.on_hit(hit, new_contact)
+    }
+
+    fn reset(&mut self) {}
+}
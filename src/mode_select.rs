@@ -0,0 +1,68 @@
+use prism::Context;
+use stork::Canvas;
+
+use crate::mode::ModeKind;
+use crate::records::Records;
+use crate::score::{DigitStyle, ScoreManager};
+use crate::ui::{solid, Overlay};
+
+const TILE_SIZE: f32 = 96.0;
+const TILE_Y: f32 = 200.0;
+const FIRST_TILE_X: f32 = 70.0;
+const TILE_SPACING: f32 = 112.0;
+
+/// Between-runs screen with one entry per mode and its best score, driven with "a"/"d" and "e".
+#[derive(Debug)]
+pub struct ModeSelect {
+    cursor: usize,
+    overlay: Overlay,
+}
+
+impl ModeSelect {
+    pub fn new() -> Self {
+        Self {
+            cursor: 0,
+            overlay: Overlay::new("menu"),
+        }
+    }
+
+    pub fn open_on(&mut self, mode: ModeKind) {
+        self.cursor = ModeKind::ALL.iter().position(|kind| *kind == mode).unwrap_or(0);
+    }
+
+    /// Handles a key and returns the mode that was just picked, if any.
+    pub fn handle_key(&mut self, key: &str) -> Option<ModeKind> {
+        let count = ModeKind::ALL.len();
+
+        match key {
+            "a" => self.cursor = (self.cursor + count - 1) % count,
+            "d" => self.cursor = (self.cursor + 1) % count,
+            "e" => return Some(ModeKind::ALL[self.cursor]),
+            _ => {}
+        }
+
+        None
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, records: &Records, score_manager: &ScoreManager) {
+        self.clear(canvas);
+
+        self.overlay.add(ctx, canvas, "mode_overlay", solid([0, 0, 0, 170]), (0.0, 0.0), (2000.0, 2000.0));
+
+        let cursor_x = FIRST_TILE_X + self.cursor as f32 * TILE_SPACING;
+        self.overlay.add(ctx, canvas, "mode_cursor", solid([255, 255, 255, 255]), (cursor_x - 4.0, TILE_Y - 4.0), (TILE_SIZE + 8.0, TILE_SIZE + 8.0));
+
+        for (idx, mode) in ModeKind::ALL.iter().enumerate() {
+            let x = FIRST_TILE_X + idx as f32 * TILE_SPACING;
+            self.overlay.add(ctx, canvas, &format!("mode_tile_{}", idx), mode.icon(TILE_SIZE as u32), (x, TILE_Y), (TILE_SIZE, TILE_SIZE));
+
+            let prefix = format!("mode_best_{}", idx);
+            score_manager.draw_number(ctx, canvas, &prefix, records.best(*mode), (x + TILE_SIZE, TILE_Y + TILE_SIZE + 12.0), DigitStyle::SMALL);
+            self.overlay.track_digits(&prefix);
+        }
+    }
+
+    pub fn clear(&mut self, canvas: &mut Canvas) {
+        self.overlay.clear(canvas);
+    }
+}
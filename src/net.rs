@@ -0,0 +1,272 @@
+use serde::{Serialize, Deserialize};
+use std::sync::mpsc;
+
+use crate::obstacle::GeneratorKind;
+use crate::rollback::{InputLog, Rollback};
+
+pub const DEFAULT_PORT: u16 = 7777;
+/// Bumped whenever `Message` changes shape; both sides must match.
+pub const PROTOCOL_VERSION: u32 = 3;
+/// Where both birds start a round, matching `Game`'s spawn point.
+pub const START_Y: f32 = 300.0;
+
+/// One line of JSON on the wire.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Message {
+    /// Sent by the host as soon as someone joins: the course both sides play.
+    Hello { version: u32, generator: GeneratorKind, seed: u32 },
+    /// Sent every tick: the sender's flaps from recent ticks, complete up to and including `up_to`.
+    Inputs { round: u32, up_to: u64, flaps: Vec<u64> },
+    /// The sender has finished `round` and is waiting for the next one.
+    Ready { round: u32 },
+}
+
+#[derive(Debug)]
+pub enum NetEvent {
+    Connected { generator: GeneratorKind, seed: u32 },
+    Received(Message),
+    Disconnected(String),
+}
+
+/// A connection to one opponent, run on its own tokio runtime so the game loop only polls.
+#[derive(Debug)]
+pub struct NetSession {
+    /// Only held so the connection's tasks live as long as the session.
+    #[cfg(not(target_arch = "wasm32"))]
+    _runtime: tokio::runtime::Runtime,
+    #[cfg(not(target_arch = "wasm32"))]
+    outgoing: tokio::sync::mpsc::UnboundedSender<Message>,
+    incoming: mpsc::Receiver<NetEvent>,
+}
+
+impl NetSession {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn runtime() -> Result<tokio::runtime::Runtime, String> {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Failed to start networking: {}", e))
+    }
+
+    /// Waits for one opponent on `port` and sends them the course once they connect.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn host(port: u16, generator: GeneratorKind, seed: u32) -> Result<Self, String> {
+        let runtime = Self::runtime()?;
+        let listener = runtime.block_on(tokio::net::TcpListener::bind(("0.0.0.0", port)))
+            .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+
+        let (events, incoming) = mpsc::channel();
+        let (outgoing, outgoing_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        runtime.spawn(async move {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    let _ = events.send(NetEvent::Disconnected(format!("Failed to accept: {}", e)));
+                    return;
+                }
+            };
+
+            let (reader, mut writer) = stream.into_split();
+            let hello = Message::Hello { version: PROTOCOL_VERSION, generator, seed };
+            if let Err(e) = write_message(&mut writer, &hello).await {
+                let _ = events.send(NetEvent::Disconnected(e));
+                return;
+            }
+
+            let _ = events.send(NetEvent::Connected { generator, seed });
+            tokio::spawn(write_loop(writer, outgoing_rx));
+            read_loop(reader, events, false).await;
+        });
+
+        Ok(Self { _runtime: runtime, outgoing, incoming })
+    }
+
+    /// Connects to a host at `address`; `Connected` arrives once its course does.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn join(address: &str) -> Result<Self, String> {
+        let runtime = Self::runtime()?;
+        let (events, incoming) = mpsc::channel();
+        let (outgoing, outgoing_rx) = tokio::sync::mpsc::unbounded_channel();
+        let address = address.to_string();
+
+        runtime.spawn(async move {
+            let stream = match tokio::net::TcpStream::connect(&address).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let _ = events.send(NetEvent::Disconnected(format!("Failed to reach {}: {}", address, e)));
+                    return;
+                }
+            };
+
+            let (reader, writer) = stream.into_split();
+            tokio::spawn(write_loop(writer, outgoing_rx));
+            read_loop(reader, events, true).await;
+        });
+
+        Ok(Self { _runtime: runtime, outgoing, incoming })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn host(_port: u16, _generator: GeneratorKind, _seed: u32) -> Result<Self, String> {
+        Err("Online play isn't available here".to_string())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn join(_address: &str) -> Result<Self, String> {
+        Err("Online play isn't available here".to_string())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn send(&self, message: Message) {
+        // A closed channel means the connection is gone, which `poll` reports on its own.
+        let _ = self.outgoing.send(message);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn send(&self, _message: Message) {}
+
+    /// Everything that happened since the last poll.
+    pub fn poll(&self) -> Vec<NetEvent> {
+        self.incoming.try_iter().collect()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn write_message(writer: &mut tokio::net::tcp::OwnedWriteHalf, message: &Message) -> Result<(), String> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut line = serde_json::to_vec(message).expect("Failed to serialize message");
+    line.push(b'\n');
+    writer.write_all(&line).await.map_err(|e| format!("Connection lost: {}", e))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn write_loop(mut writer: tokio::net::tcp::OwnedWriteHalf, mut outgoing: tokio::sync::mpsc::UnboundedReceiver<Message>) {
+    while let Some(message) = outgoing.recv().await {
+        if write_message(&mut writer, &message).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Forwards messages to the game until the connection drops. A joining side waits for `Hello` first.
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_loop(reader: tokio::net::tcp::OwnedReadHalf, events: mpsc::Sender<NetEvent>, mut awaiting_hello: bool) {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut lines = tokio::io::BufReader::new(reader).lines();
+
+    let reason = loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break "Opponent left".to_string(),
+            Err(e) => break format!("Connection lost: {}", e),
+        };
+
+        let message: Message = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => break format!("Unreadable message from opponent: {}", e),
+        };
+
+        let event = match (std::mem::replace(&mut awaiting_hello, false), message) {
+            (true, Message::Hello { version, generator, seed }) if version == PROTOCOL_VERSION => {
+                NetEvent::Connected { generator, seed }
+            }
+            (true, Message::Hello { version, .. }) => {
+                break format!("Host speaks protocol {}, expected {}", version, PROTOCOL_VERSION);
+            }
+            (true, _) => break "Host didn't send a course".to_string(),
+            (false, message) => NetEvent::Received(message),
+        };
+
+        if events.send(event).is_err() {
+            return;
+        }
+    };
+
+    let _ = events.send(NetEvent::Disconnected(reason));
+}
+
+/// Round bookkeeping for an online versus match.
+#[derive(Debug)]
+pub struct Online {
+    pub session: NetSession,
+    pub inputs: InputLog,
+    /// Both sides' flaps this round, and the game to roll back when the opponent's come in late.
+    pub rollback: Rollback,
+    /// Ticks before this one have had their input sent; it can't change once it has gone out.
+    next_unsent: u64,
+    /// Inputs for the next round that arrived while this side was still finishing the last one.
+    early: Vec<(u64, Vec<u64>)>,
+    pub round: u32,
+    /// This side has finished the round and is waiting for the opponent.
+    pub waiting: bool,
+    pub remote_ready: bool,
+}
+
+impl Online {
+    pub fn new(session: NetSession) -> Self {
+        Self {
+            session,
+            inputs: InputLog::new(),
+            rollback: Rollback::new(),
+            next_unsent: 0,
+            early: Vec::new(),
+            round: 0,
+            waiting: false,
+            remote_ready: false,
+        }
+    }
+
+    /// Sends this tick's input along with the last few flaps. A tick held while the opponent
+    /// confirms the end of the round keeps the input it first went out with.
+    pub fn send_inputs(&mut self, flapped: bool) {
+        let tick = self.rollback.tick();
+
+        if tick >= self.next_unsent {
+            self.rollback.record_local(flapped);
+            if flapped {
+                self.inputs.record(tick);
+            }
+            self.next_unsent = tick + 1;
+        }
+
+        let flaps = self.inputs.recent(tick);
+        self.session.send(Message::Inputs { round: self.round, up_to: tick, flaps });
+    }
+
+    pub fn receive_inputs(&mut self, round: u32, up_to: u64, flaps: Vec<u64>) {
+        if round == self.round && !self.waiting {
+            self.rollback.receive(up_to, &flaps);
+        } else if round > self.round {
+            self.early.push((up_to, flaps));
+        }
+    }
+
+    pub fn finish_round(&mut self) {
+        self.session.send(Message::Ready { round: self.round });
+        self.waiting = true;
+    }
+
+    /// Starts the next round once both sides have finished the last one.
+    pub fn try_start_round(&mut self) -> bool {
+        if self.waiting && !self.remote_ready {
+            return false;
+        }
+
+        self.round += 1;
+        self.inputs = InputLog::new();
+        self.rollback = Rollback::new();
+        self.next_unsent = 0;
+
+        for (up_to, flaps) in std::mem::take(&mut self.early) {
+            self.rollback.receive(up_to, &flaps);
+        }
+        self.waiting = false;
+        self.remote_ready = false;
+        true
+    }
+}
@@ -0,0 +1,350 @@
+use rand::{Rng, RngCore};
+use serde::{Serialize, Deserialize};
+use std::f32::consts::TAU;
+
+use crate::pipe::{PipePair, PipeVariant, PIPE_SPAWN_INTERVAL};
+use crate::powerup::PowerUpKind;
+
+/// Something placed in the middle of a gap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pickup {
+    Coin,
+    PowerUp(PowerUpKind),
+}
+
+/// What the next pipe pair should look like; `gap_y` is the gap centre.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ObstacleSpec {
+    pub gap_y: f32,
+    pub gap_size: f32,
+    pub variant: PipeVariant,
+    /// Seconds after the previous pair spawned.
+    pub delay: f32,
+    pub pickup: Option<Pickup>,
+}
+
+impl ObstacleSpec {
+    /// A pair on the regular spawn interval with nothing in the gap.
+    pub fn new(gap_y: f32, gap_size: f32, variant: PipeVariant) -> Self {
+        Self {
+            gap_y,
+            gap_size,
+            variant,
+            delay: PIPE_SPAWN_INTERVAL,
+            pickup: None,
+        }
+    }
+}
+
+/// Everything a generator may look at when laying out the next pair.
+#[derive(Debug, Clone, Copy)]
+pub struct GeneratorContext<'a> {
+    pub pair_index: u32,
+    pub previous: Option<&'a PipePair>,
+    pub gap_size: f32,
+    /// Range the gap centre may take without clipping the ceiling or ground.
+    pub bounds: (f32, f32),
+    /// Furthest the gap centre may climb and drop from the previous pair and stay reachable.
+    pub reach: (f32, f32),
+}
+
+impl GeneratorContext<'_> {
+    /// Clamps `gap_y` to the playable bounds.
+    pub fn clamp(&self, gap_y: f32) -> f32 {
+        gap_y.clamp(self.bounds.0, self.bounds.1)
+    }
+
+    /// Range the next gap centre may take while staying reachable from the previous pair.
+    pub fn reachable_range(&self) -> (f32, f32) {
+        let Some(previous) = self.previous else {
+            return self.bounds;
+        };
+
+        let low = (previous.base_gap_y - self.reach.0).max(self.bounds.0);
+        let high = (previous.base_gap_y + self.reach.1).min(self.bounds.1);
+        (low, high.max(low))
+    }
+}
+
+/// Lays out pipe pairs one at a time; `PipeManager` handles spawning, scrolling and removal.
+pub trait ObstacleGenerator: std::fmt::Debug {
+    fn name(&self) -> &str;
+
+    /// The next pair, or `None` once the course has run out.
+    fn next(&mut self, ctx: &GeneratorContext, rng: &mut dyn RngCore) -> Option<ObstacleSpec>;
+
+    /// Starts the course again from its first pair.
+    fn reset(&mut self);
+
+    /// Authored courses are played exactly as written: no reachability fallback and no random pickups.
+    fn is_authored(&self) -> bool {
+        false
+    }
+
+    /// Seconds after the last pair at which the finish line spawns, for courses that have one.
+    fn finish(&self) -> Option<f32> {
+        None
+    }
+
+    /// Pairs still to spawn, for courses that run out.
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+
+    /// Where the generator is in its course, for snapshots.
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// Picks up from a state returned by `save_state`.
+    fn load_state(&mut self, _state: &serde_json::Value) {}
+}
+
+/// Uniform random gaps within reach of the previous pair, mixing in moving pipes over a run.
+#[derive(Debug, Clone, Default)]
+pub struct ClassicGenerator;
+
+impl ObstacleGenerator for ClassicGenerator {
+    fn name(&self) -> &str {
+        "classic"
+    }
+
+    fn next(&mut self, ctx: &GeneratorContext, mut rng: &mut dyn RngCore) -> Option<ObstacleSpec> {
+        let (low, high) = ctx.reachable_range();
+        let gap_y = if high > low { rng.random_range(low..high) } else { low };
+
+        let variant = PipeVariant::pick(ctx.pair_index, ctx.gap_size, &mut rng);
+        Some(ObstacleSpec::new(gap_y, ctx.gap_size, variant))
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Gaps that follow a sine wave, so the bird weaves through a smooth corridor.
+#[derive(Debug, Clone)]
+pub struct SineCorridor {
+    /// Share of the playable height the wave swings across, from 0 to 1.
+    pub amplitude: f32,
+    /// Pairs per full wave.
+    pub wavelength: f32,
+    phase: f32,
+}
+
+impl SineCorridor {
+    pub fn new(amplitude: f32, wavelength: f32) -> Self {
+        Self {
+            amplitude: amplitude.clamp(0.0, 1.0),
+            wavelength: wavelength.max(1.0),
+            phase: 0.0,
+        }
+    }
+}
+
+impl Default for SineCorridor {
+    fn default() -> Self {
+        Self::new(0.8, 8.0)
+    }
+}
+
+impl ObstacleGenerator for SineCorridor {
+    fn name(&self) -> &str {
+        "sine"
+    }
+
+    fn next(&mut self, ctx: &GeneratorContext, _rng: &mut dyn RngCore) -> Option<ObstacleSpec> {
+        let middle = (ctx.bounds.0 + ctx.bounds.1) / 2.0;
+        let half_span = (ctx.bounds.1 - ctx.bounds.0) / 2.0;
+        let gap_y = middle + half_span * self.amplitude * self.phase.sin();
+
+        self.phase += TAU / self.wavelength;
+
+        Some(ObstacleSpec::new(ctx.clamp(gap_y), ctx.gap_size, PipeVariant::Static))
+    }
+
+    fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::json!(self.phase)
+    }
+
+    fn load_state(&mut self, state: &serde_json::Value) {
+        self.phase = state.as_f64().unwrap_or(0.0) as f32;
+    }
+}
+
+/// Gaps that climb or descend in even steps and turn around at the top and bottom.
+#[derive(Debug, Clone)]
+pub struct Staircase {
+    /// Pixels the gap moves per pair.
+    pub step: f32,
+    /// Pairs on the same level before the next step.
+    pub landing: u32,
+    level: Option<f32>,
+    direction: f32,
+    count: u32,
+}
+
+impl Staircase {
+    pub fn new(step: f32, landing: u32) -> Self {
+        Self {
+            step: step.abs(),
+            landing: landing.max(1),
+            level: None,
+            direction: -1.0,
+            count: 0,
+        }
+    }
+}
+
+impl Default for Staircase {
+    fn default() -> Self {
+        Self::new(60.0, 2)
+    }
+}
+
+impl ObstacleGenerator for Staircase {
+    fn name(&self) -> &str {
+        "staircase"
+    }
+
+    fn next(&mut self, ctx: &GeneratorContext, _rng: &mut dyn RngCore) -> Option<ObstacleSpec> {
+        let mut level = self.level.unwrap_or(ctx.bounds.1);
+
+        if self.level.is_some() && self.count.is_multiple_of(self.landing) {
+            let step = self.step.min(if self.direction < 0.0 { ctx.reach.0 } else { ctx.reach.1 });
+            let stepped = level + self.direction * step;
+
+            if stepped < ctx.bounds.0 || stepped > ctx.bounds.1 {
+                self.direction = -self.direction;
+            }
+            level = ctx.clamp(level + self.direction * step);
+        }
+
+        self.level = Some(level);
+        self.count += 1;
+
+        Some(ObstacleSpec::new(level, ctx.gap_size, PipeVariant::Static))
+    }
+
+    fn reset(&mut self) {
+        self.level = None;
+        self.direction = -1.0;
+        self.count = 0;
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::json!([self.level, self.direction, self.count])
+    }
+
+    fn load_state(&mut self, state: &serde_json::Value) {
+        if let Ok((level, direction, count)) = serde_json::from_value(state.clone()) {
+            (self.level, self.direction, self.count) = (level, direction, count);
+        }
+    }
+}
+
+/// A fixed list of pairs laid out by hand, optionally played on repeat.
+#[derive(Debug, Clone)]
+pub struct AuthoredCourse {
+    pub name: String,
+    pub obstacles: Vec<ObstacleSpec>,
+    pub looping: bool,
+    /// Seconds after the last pair at which the finish line spawns.
+    pub finish: Option<f32>,
+    cursor: usize,
+}
+
+impl AuthoredCourse {
+    pub fn new(name: &str, obstacles: Vec<ObstacleSpec>, looping: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            obstacles,
+            looping,
+            finish: None,
+            cursor: 0,
+        }
+    }
+
+    pub fn with_finish(mut self, delay: f32) -> Self {
+        self.finish = Some(delay.max(0.0));
+        self
+    }
+}
+
+impl ObstacleGenerator for AuthoredCourse {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn next(&mut self, _ctx: &GeneratorContext, _rng: &mut dyn RngCore) -> Option<ObstacleSpec> {
+        if self.cursor >= self.obstacles.len() {
+            if !self.looping || self.obstacles.is_empty() {
+                return None;
+            }
+            self.cursor = 0;
+        }
+
+        let spec = self.obstacles[self.cursor];
+        self.cursor += 1;
+        Some(spec)
+    }
+
+    fn reset(&mut self) {
+        self.cursor = 0;
+    }
+
+    fn is_authored(&self) -> bool {
+        true
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        (!self.looping).then(|| self.obstacles.len().saturating_sub(self.cursor))
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::json!(self.cursor)
+    }
+
+    fn load_state(&mut self, state: &serde_json::Value) {
+        self.cursor = state.as_u64().unwrap_or(0) as usize;
+    }
+
+    fn finish(&self) -> Option<f32> {
+        if self.looping { None } else { self.finish }
+    }
+}
+
+/// The built-in generators, by name, for picking one per mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GeneratorKind {
+    Classic,
+    SineCorridor,
+    Staircase,
+}
+
+impl GeneratorKind {
+    pub const ALL: [GeneratorKind; 3] = [GeneratorKind::Classic, GeneratorKind::SineCorridor, GeneratorKind::Staircase];
+
+    /// Stable number for the kind, used in challenge codes.
+    pub fn index(&self) -> u8 {
+        match self {
+            GeneratorKind::Classic => 0,
+            GeneratorKind::SineCorridor => 1,
+            GeneratorKind::Staircase => 2,
+        }
+    }
+
+    pub fn from_index(index: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.index() == index)
+    }
+
+    pub fn build(&self) -> Box<dyn ObstacleGenerator> {
+        match self {
+            GeneratorKind::Classic => Box::new(ClassicGenerator),
+            GeneratorKind::SineCorridor => Box::new(SineCorridor::default()),
+            GeneratorKind::Staircase => Box::new(Staircase::default()),
+        }
+    }
+}
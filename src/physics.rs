@@ -0,0 +1,32 @@
+/// Bird physics parameters, mirroring how the stork canvas integrates a `GameObject` each tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Physics {
+    pub gravity: f32,
+    pub resistance: (f32, f32),
+    pub flap_impulse: f32,
+    /// Seconds per tick.
+    pub tick: f32,
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Self {
+            gravity: 0.30,
+            resistance: (0.85, 0.85),
+            flap_impulse: -10.5,
+            tick: 0.016,
+        }
+    }
+}
+
+impl Physics {
+    /// Vertical position and momentum after one tick.
+    pub fn step(&self, y: f32, momentum_y: f32) -> (f32, f32) {
+        let momentum_y = (momentum_y + self.gravity) * self.resistance.1;
+        (y + momentum_y, momentum_y)
+    }
+
+    pub fn flap(&self, momentum_y: f32) -> f32 {
+        momentum_y + self.flap_impulse
+    }
+}
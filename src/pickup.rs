@@ -0,0 +1,104 @@
+use prism::Context;
+use prism::canvas::{Image, ShapeType};
+use stork::{Canvas, GameObject};
+use serde::{Serialize, Deserialize};
+
+use crate::coin::{self, COIN_SIZE};
+use crate::obstacle::Pickup;
+use crate::powerup::PICKUP_SIZE;
+
+/// A coin or power-up still on screen, by top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LivePickup {
+    pub id: u32,
+    pub position: (f32, f32),
+    pub kind: Pickup,
+}
+
+impl LivePickup {
+    pub fn size(&self) -> f32 {
+        match self.kind {
+            Pickup::Coin => COIN_SIZE,
+            Pickup::PowerUp(_) => PICKUP_SIZE,
+        }
+    }
+
+    /// Whether it overlaps the box with its top-left corner at `(left, top)`.
+    pub fn touches(&self, (left, top): (f32, f32), (width, height): (f32, f32)) -> bool {
+        let (x, y) = self.position;
+        let size = self.size();
+
+        x < left + width && x + size > left && y < top + height && y + size > top
+    }
+
+    fn image(&self) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+        match self.kind {
+            Pickup::Coin => coin::coin_image(COIN_SIZE as u32),
+            Pickup::PowerUp(kind) => kind.icon(PICKUP_SIZE as u32),
+        }
+    }
+}
+
+/// Keeps one canvas object per live pickup, added, moved and removed to match the simulation.
+#[derive(Debug, Default)]
+pub struct PickupSprites {
+    drawn: Vec<u32>,
+}
+
+impl PickupSprites {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn name(id: u32) -> String {
+        format!("pickup_{}", id)
+    }
+
+    pub fn sync(&mut self, ctx: &mut Context, canvas: &mut Canvas, pickups: &[LivePickup]) {
+        self.drawn.retain(|id| {
+            let live = pickups.iter().any(|pickup| pickup.id == *id);
+            if !live {
+                canvas.remove_game_object(&Self::name(*id));
+            }
+            live
+        });
+
+        for pickup in pickups {
+            let name = Self::name(pickup.id);
+
+            if let Some(object) = canvas.get_game_object_mut(&name) {
+                object.position = pickup.position;
+                object.momentum = (0.0, 0.0);
+                continue;
+            }
+
+            let size = pickup.size();
+            let img_obj = Image {
+                shape: ShapeType::Rectangle(0.0, (size, size), 0.0),
+                image: pickup.image().into(),
+                color: None
+            };
+
+            let object = GameObject::new_rect(
+                ctx,
+                name.clone(),
+                img_obj,
+                (size, size),
+                pickup.position,
+                vec!["pickup".to_string()],
+                (0.0, 0.0),
+                (1.0, 1.0),
+                0.0,
+            );
+
+            canvas.add_game_object(name, object);
+            self.drawn.push(pickup.id);
+        }
+    }
+
+    pub fn clear(&mut self, canvas: &mut Canvas) {
+        for id in self.drawn.drain(..) {
+            canvas.remove_game_object(&Self::name(id));
+        }
+    }
+}
@@ -2,6 +2,163 @@ use prism::Context;
 use prism::canvas::{Image, ShapeType};
 use stork::{Canvas, GameObject};
 use rand::Rng;
+use serde::{Serialize, Deserialize};
+use std::f32::consts::TAU;
+
+use crate::atlas::Atlas;
+use crate::obstacle::{ClassicGenerator, GeneratorContext, ObstacleGenerator, ObstacleSpec, Pickup};
+use crate::physics::Physics;
+use crate::reach::{ReachTable, SolverConfig};
+use crate::rng::SeededRng;
+use crate::skin::PipeSkin;
+use crate::world::WorldSpeed;
+
+pub const PIPE_SPAWN_INTERVAL: f32 = 2.0;
+/// Share of the climb or drop the reach table allows that a new gap may ask for; the slack covers
+/// what the table's estimate leaves out.
+const REACH_MARGIN: f32 = 0.8;
+/// Longest delay between pairs, in seconds, the reach table covers; longer delays use its last entry.
+const REACH_TABLE_SECONDS: f32 = 4.0;
+const FINISH_LINE_WIDTH: f32 = 24.0;
+/// Mixed into the course seed for pickup rolls, so they repeat with the course without sharing its draws.
+const PICKUP_SALT: u64 = 0x5049_434B_5550_5321;
+
+/// Black and white checks for the finish line.
+pub fn finish_line_image(width: u32, height: u32) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+    let check = (width / 2).max(1);
+
+    image::ImageBuffer::from_fn(width, height, |x, y| {
+        if (x / check + y / check).is_multiple_of(2) {
+            image::Rgba([255, 255, 255, 255])
+        } else {
+            image::Rgba([20, 20, 20, 255])
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PipeGap {
+    pub id: u32,
+    pub x: f32,
+    pub width: f32,
+    pub center_y: f32,
+    pub size: f32,
+    pub pickup: Option<Pickup>,
+}
+
+/// Narrowest a gap may get while moving, the bird is 35px tall.
+pub const MIN_PASSABLE_GAP: f32 = 150.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PipeVariant {
+    Static,
+    /// Gap centre swings up and down around where it spawned.
+    Bobbing { amplitude: f32, period: f32 },
+    /// Gap closes towards `min_size` and opens back up.
+    Breathing { min_size: f32, period: f32 },
+    /// Gap centre slides at `rate` pixels per second, bouncing off the playable bounds.
+    Drifting { rate: f32 },
+}
+
+impl PipeVariant {
+    /// Gap centre offset and gap size `age` seconds after the pair spawned.
+    pub fn motion(&self, age: f32, gap_size: f32) -> (f32, f32) {
+        match *self {
+            PipeVariant::Static => (0.0, gap_size),
+            PipeVariant::Bobbing { amplitude, period } => {
+                (amplitude * (age * TAU / period).sin(), gap_size)
+            }
+            PipeVariant::Breathing { min_size, period } => {
+                let open = 0.5 + 0.5 * (age * TAU / period).cos();
+                (0.0, min_size + (gap_size - min_size) * open)
+            }
+            PipeVariant::Drifting { rate } => (rate * age, gap_size),
+        }
+    }
+
+    /// Picks a variant for the `pair_index`th pair of a run, mixing in moving pipes as it goes on.
+    pub fn pick(pair_index: u32, gap_size: f32, rng: &mut impl Rng) -> Self {
+        let mix = ((pair_index as f32 - 5.0) / 30.0).clamp(0.0, 0.6);
+        if rng.random_range(0.0..1.0) >= mix {
+            return PipeVariant::Static;
+        }
+
+        let intensity = (pair_index as f32 / 40.0).min(1.0);
+
+        match rng.random_range(0..3) {
+            0 => PipeVariant::Bobbing {
+                amplitude: 30.0 + 50.0 * intensity,
+                period: rng.random_range(2.0..3.0),
+            },
+            1 => PipeVariant::Breathing {
+                min_size: (gap_size * (0.85 - 0.2 * intensity)).max(MIN_PASSABLE_GAP),
+                period: rng.random_range(1.5..2.5),
+            },
+            _ => {
+                let direction = if rng.random_range(0..2) == 0 { -1.0 } else { 1.0 };
+                PipeVariant::Drifting { rate: direction * (20.0 + 40.0 * intensity) }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipePair {
+    pub id: u32,
+    pub x: f32,
+    pub base_gap_y: f32,
+    pub gap_size: f32,
+    pub variant: PipeVariant,
+    pub age: f32,
+}
+
+impl PipePair {
+    /// Gap centre and size `extra_age` seconds from now, clamped so the gap stays passable.
+    pub fn gap_after(&self, extra_age: f32, canvas_height: f32) -> (f32, f32) {
+        let (offset, size) = self.variant.motion(self.age + extra_age, self.gap_size);
+        let size = size.max(MIN_PASSABLE_GAP);
+        let (min_gap_y, max_gap_y) = gap_bounds(canvas_height, size);
+
+        (fold_into(self.base_gap_y + offset, min_gap_y, max_gap_y), size)
+    }
+
+    pub fn gap_at(&self, canvas_height: f32) -> (f32, f32) {
+        self.gap_after(0.0, canvas_height)
+    }
+}
+
+/// Range the gap centre may take for a gap of `size` without clipping the ceiling or ground.
+pub fn gap_bounds(canvas_height: f32, size: f32) -> (f32, f32) {
+    let base_height = 112.0;
+    let min_gap_y = 150.0;
+    let max_gap_y = canvas_height - base_height - size / 2.0 - 10.0;
+
+    (min_gap_y, max_gap_y.max(min_gap_y))
+}
+
+/// Saved `PipeManager` state, see `PipeManager::snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipeSnapshot {
+    pub pipe_spawn_timer: f32,
+    pub pipe_counter: u32,
+    pub pairs: Vec<PipePair>,
+    pub rng: SeededRng,
+    pub pickup_rng: SeededRng,
+    pub pending: Option<ObstacleSpec>,
+    pub exhausted: bool,
+    pub finish_x: Option<f32>,
+    pub course_complete: bool,
+    pub generator: serde_json::Value,
+}
+
+/// What one `PipeManager::step` changed, so `draw_step` can bring the canvas in line.
+#[derive(Debug, Default)]
+pub struct PipeStep {
+    pub spawned: Option<PipeGap>,
+    pub finish_line: bool,
+    /// Ids of pairs that scrolled off screen.
+    pub removed: Vec<u32>,
+}
 
 #[derive(Debug)]
 pub struct PipeManager {
@@ -12,7 +169,24 @@ pub struct PipeManager {
     pub pipe_width: f32,
     pub pipe_height: f32,
     pub gap_size: f32,
+    /// Seconds between generated pairs; authored courses keep their own timing.
+    pub spawn_interval: f32,
+    pub pairs: Vec<PipePair>,
     canvas_size: (f32, f32),
+    physics: Physics,
+    solver: SolverConfig,
+    /// Climb and drop limits for this physics, so checking a new pair never runs the solver.
+    reach: ReachTable,
+    generator: Box<dyn ObstacleGenerator>,
+    rng: SeededRng,
+    /// Rolls for pickups in this course's gaps, seeded alongside `rng`.
+    pickup_rng: SeededRng,
+    /// Fixed course seed, or `None` for a fresh course every run.
+    seed: Option<u64>,
+    pending: Option<ObstacleSpec>,
+    exhausted: bool,
+    finish_x: Option<f32>,
+    course_complete: bool,
 }
 
 impl PipeManager {
@@ -21,50 +195,291 @@ impl PipeManager {
         pipe_height: f32,
         gap_size: f32,
         canvas_size: (f32, f32),
+        skin: PipeSkin,
+        atlas: &Atlas,
     ) -> Self {
-        let toppipe_bytes = include_bytes!("../assets/toppipe.png");
-        let toppipe_img = image::load_from_memory(toppipe_bytes)
-            .expect("Failed to load toppipe image");
-        let toppipe_image = toppipe_img.to_rgba8();
-        
-        let bottompipe_bytes = include_bytes!("../assets/bottompipe.png");
-        let bottompipe_img = image::load_from_memory(bottompipe_bytes)
-            .expect("Failed to load bottompipe image");
-        let bottompipe_image = bottompipe_img.to_rgba8();
+        let (toppipe_image, bottompipe_image) = skin.images(atlas);
 
         Self {
-            pipe_spawn_timer: 0.0,
-            pipe_counter: 0,
             toppipe_image,
             bottompipe_image,
+            ..Self::headless(pipe_width, pipe_height, gap_size, canvas_size)
+        }
+    }
+
+    /// A manager with no pipe images, for running courses without a canvas.
+    pub fn headless(pipe_width: f32, pipe_height: f32, gap_size: f32, canvas_size: (f32, f32)) -> Self {
+        let physics = Physics::default();
+        let solver = SolverConfig::for_canvas(canvas_size, pipe_width);
+        let rng = SeededRng::from_entropy();
+
+        Self {
+            pipe_spawn_timer: 0.0,
+            pipe_counter: 0,
+            toppipe_image: image::ImageBuffer::new(0, 0),
+            bottompipe_image: image::ImageBuffer::new(0, 0),
             pipe_width,
             pipe_height,
             gap_size,
+            spawn_interval: PIPE_SPAWN_INTERVAL,
+            pairs: Vec::new(),
             canvas_size,
+            physics,
+            solver,
+            reach: Self::reach_table(&physics, &solver),
+            generator: Box::new(ClassicGenerator),
+            rng,
+            pickup_rng: SeededRng::new(rng.seed ^ PICKUP_SALT),
+            seed: None,
+            pending: None,
+            exhausted: false,
+            finish_x: None,
+            course_complete: false,
         }
     }
 
-    pub fn update(&mut self, ctx: &mut Context, canvas: &mut Canvas) {
-        const PIPE_SPAWN_INTERVAL: f32 = 2.0;
-        
-        self.pipe_spawn_timer += 0.016;
+    pub fn with_physics(mut self, physics: Physics, solver: SolverConfig) -> Self {
+        self.physics = physics;
+        self.solver = solver;
+        self.reach = Self::reach_table(&physics, &solver);
+        self
+    }
+
+    /// Spaces generated pairs `spawn_interval` seconds apart instead of `PIPE_SPAWN_INTERVAL`.
+    pub fn with_spawn_interval(mut self, spawn_interval: f32) -> Self {
+        self.spawn_interval = spawn_interval;
+        self
+    }
+
+    /// Lays out pairs spawned from now on with `generator`, starting it from the top.
+    pub fn set_generator(&mut self, mut generator: Box<dyn ObstacleGenerator>) {
+        generator.reset();
+        self.generator = generator;
+    }
+
+    /// Lays out the same course on every run from now on, or a fresh one each run with `None`.
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+        self.rng = seed.map(SeededRng::new).unwrap_or_else(SeededRng::from_entropy);
+        self.pickup_rng = SeededRng::new(self.rng.seed ^ PICKUP_SALT);
+    }
+
+    /// Seed of the course being played.
+    pub fn seed(&self) -> u64 {
+        self.rng.seed
+    }
+
+    pub fn generator(&self) -> &dyn ObstacleGenerator {
+        self.generator.as_ref()
+    }
+
+    /// Generator for random pickups, so a seeded course gets the same ones every time it's played.
+    pub fn pickup_rng(&mut self) -> &mut SeededRng {
+        &mut self.pickup_rng
+    }
+
+    fn reach_table(physics: &Physics, solver: &SolverConfig) -> ReachTable {
+        ReachTable::new(physics, solver, (REACH_TABLE_SECONDS / physics.tick).round() as u32)
+    }
+
+    fn ticks(&self, seconds: f32) -> u32 {
+        (seconds / self.physics.tick).round() as u32
+    }
+
+    /// Swaps the pipe images used for pairs spawned from now on.
+    pub fn set_skin(&mut self, skin: PipeSkin, atlas: &Atlas) {
+        (self.toppipe_image, self.bottompipe_image) = skin.images(atlas);
+    }
+
+    /// Brings the canvas in line with what `step` just changed.
+    pub fn draw_step(&self, ctx: &mut Context, canvas: &mut Canvas, step: &PipeStep) {
+        if let Some(pair) = step.spawned.and_then(|gap| self.pairs.iter().find(|pair| pair.id == gap.id)) {
+            self.spawn_pair_objects(ctx, canvas, pair);
+        }
+
+        if step.finish_line {
+            self.spawn_finish_line(ctx, canvas);
+        }
+
+        self.place_pairs(canvas);
+
+        for id in &step.removed {
+            canvas.remove_game_object(&format!("toppipe_{}", id));
+            canvas.remove_game_object(&format!("bottompipe_{}", id));
+        }
+    }
+
+    /// Adds every live pair and the finish line to the canvas, after `clear` or a restore.
+    pub fn draw_all(&self, ctx: &mut Context, canvas: &mut Canvas) {
+        for pair in &self.pairs {
+            self.spawn_pair_objects(ctx, canvas, pair);
+        }
+
+        if self.finish_x.is_some() {
+            self.spawn_finish_line(ctx, canvas);
+        }
+    }
+
+    /// Takes every live pair and the finish line off the canvas, leaving the course as it is.
+    pub fn clear(&self, canvas: &mut Canvas) {
+        for pair in &self.pairs {
+            canvas.remove_game_object(&format!("toppipe_{}", pair.id));
+            canvas.remove_game_object(&format!("bottompipe_{}", pair.id));
+        }
+
+        canvas.remove_game_object("finish_line");
+    }
+
+    /// Moves the course on one tick without touching the canvas, which is all a headless run needs.
+    pub fn step(&mut self, world_speed: &WorldSpeed) -> PipeStep {
+        let mut step = PipeStep::default();
+        self.pipe_spawn_timer += self.physics.tick * world_speed.ratio();
+
+        if self.pending.is_none() && !self.exhausted {
+            self.pending = self.next_spec();
+            self.exhausted = self.pending.is_none();
+        }
         
-        if self.pipe_spawn_timer >= PIPE_SPAWN_INTERVAL {
-            self.spawn_pipe_pair(ctx, canvas);
-            self.pipe_spawn_timer = 0.0;
+        if let Some(spec) = self.pending {
+            if self.pipe_spawn_timer >= spec.delay {
+                step.spawned = Some(self.push_pair(spec));
+                self.pending = None;
+                self.pipe_spawn_timer = 0.0;
+            }
+        } else if let (None, Some(delay)) = (self.finish_x, self.generator.finish()) {
+            if self.pipe_spawn_timer >= delay {
+                self.finish_x = Some(self.canvas_size.0 + 100.0);
+                step.finish_line = true;
+            }
         }
         
-        self.remove_offscreen_pipes(canvas);
+        self.advance_pairs(world_speed);
+        step.removed = self.remove_offscreen_pipes();
+        self.check_course_complete();
+
+        step
     }
 
-    pub fn spawn_pipe_pair(&mut self, ctx: &mut Context, canvas: &mut Canvas) {
-        let mut rng = rand::thread_rng();
-        
-        let base_height = 112.0;
-        let min_gap_y = 150.0;
-        let max_gap_y = self.canvas_size.1 - base_height - self.gap_size / 2.0 - 10.0;
-        let gap_y = rng.gen_range(min_gap_y..max_gap_y);
+    /// True once an authored course has run out and its last pair or finish line is behind the bird.
+    pub fn is_course_complete(&self) -> bool {
+        self.course_complete
+    }
+
+    fn check_course_complete(&mut self) {
+        if !self.exhausted || self.course_complete {
+            return;
+        }
+
+        let bird_x = self.solver.bird_x;
+        self.course_complete = match (self.generator.finish(), self.finish_x) {
+            (Some(_), Some(finish_x)) => finish_x + FINISH_LINE_WIDTH < bird_x,
+            (Some(_), None) => false,
+            (None, _) => self.pairs.iter().all(|pair| pair.x + self.pipe_width < bird_x),
+        };
+    }
+
+    fn spawn_finish_line(&self, ctx: &mut Context, canvas: &mut Canvas) {
+        let x = self.finish_x.unwrap_or(self.canvas_size.0 + 100.0);
+        let height = self.solver.ground_y;
+
+        let img_obj = Image {
+            shape: ShapeType::Rectangle(0.0, (FINISH_LINE_WIDTH, height), 0.0),
+            image: finish_line_image(FINISH_LINE_WIDTH as u32, height as u32).into(),
+            color: None
+        };
+
+        let finish_line = GameObject::new_rect(
+            ctx,
+            "finish_line".to_string(),
+            img_obj,
+            (FINISH_LINE_WIDTH, height),
+            (x, 0.0),
+            vec!["finish".to_string()],
+            (0.0, 0.0),
+            (1.0, 1.0),
+            0.0,
+        );
+
+        canvas.add_game_object("finish_line".to_string(), finish_line);
+    }
+
+    /// Scrolls every live pair and the finish line.
+    pub fn advance_pairs(&mut self, world_speed: &WorldSpeed) {
+        for pair in &mut self.pairs {
+            pair.x += world_speed.value();
+            pair.age += self.physics.tick * world_speed.ratio();
+        }
+
+        if let Some(finish_x) = &mut self.finish_x {
+            *finish_x += world_speed.value();
+        }
+    }
+
+    /// Moves every pair's pipes to where its variant puts the gap, and the finish line with them.
+    fn place_pairs(&self, canvas: &mut Canvas) {
+        for pair in &self.pairs {
+            self.place_pair(canvas, pair);
+        }
+
+        if let (Some(finish_x), Some(finish_line)) = (self.finish_x, canvas.get_game_object_mut("finish_line")) {
+            finish_line.position = (finish_x, 0.0);
+            finish_line.momentum = (0.0, 0.0);
+        }
+    }
+
+    fn place_pair(&self, canvas: &mut Canvas, pair: &PipePair) {
+        let (center_y, size) = self.resolve_gap(pair);
+
+        if let Some(toppipe) = canvas.get_game_object_mut(&format!("toppipe_{}", pair.id)) {
+            toppipe.position = (pair.x, center_y - size / 2.0 - self.pipe_height);
+            toppipe.momentum = (0.0, 0.0);
+        }
+
+        if let Some(bottompipe) = canvas.get_game_object_mut(&format!("bottompipe_{}", pair.id)) {
+            bottompipe.position = (pair.x, center_y + size / 2.0);
+            bottompipe.momentum = (0.0, 0.0);
+        }
+    }
+
+    pub fn gap_bounds(&self, size: f32) -> (f32, f32) {
+        gap_bounds(self.canvas_size.1, size)
+    }
+
+    pub fn resolve_gap(&self, pair: &PipePair) -> (f32, f32) {
+        pair.gap_at(self.canvas_size.1)
+    }
+
+    /// Adds a pair laid out by `spec` at the spawn point and returns its gap.
+    fn push_pair(&mut self, spec: ObstacleSpec) -> PipeGap {
+        let (gap_y, gap_size) = (spec.gap_y, spec.gap_size);
+
+        let pair = PipePair {
+            id: self.pipe_counter,
+            x: self.canvas_size.0 + 100.0,
+            base_gap_y: gap_y,
+            gap_size,
+            variant: spec.variant,
+            age: 0.0,
+        };
         
+        let gap = PipeGap {
+            id: self.pipe_counter,
+            x: pair.x,
+            width: self.pipe_width,
+            center_y: gap_y,
+            size: gap_size,
+            pickup: spec.pickup,
+        };
+
+        self.pairs.push(pair);
+        self.pipe_counter += 1;
+
+        gap
+    }
+
+    fn spawn_pair_objects(&self, ctx: &mut Context, canvas: &mut Canvas, pair: &PipePair) {
+        let (center_y, size) = self.resolve_gap(pair);
+
         let toppipe_img_obj = Image {
             shape: ShapeType::Rectangle(0.0, (self.pipe_width, self.pipe_height), 0.0),
             image: self.toppipe_image.clone().into(),
@@ -79,66 +494,166 @@ impl PipeManager {
         
         let toppipe = GameObject::new_rect(
             ctx,
-            format!("toppipe_{}", self.pipe_counter),
+            format!("toppipe_{}", pair.id),
             toppipe_img_obj,
             (self.pipe_width, self.pipe_height),
-            (self.canvas_size.0 + 100.0, gap_y - self.gap_size / 2.0 - self.pipe_height),
+            (pair.x, center_y - size / 2.0 - self.pipe_height),
             vec!["pipe".to_string(), "obstacle".to_string()],
-            (-3.0, 0.0),
+            (0.0, 0.0),
             (1.0, 1.0),
             0.0,
         );
         
         let bottompipe = GameObject::new_rect(
             ctx,
-            format!("bottompipe_{}", self.pipe_counter),
+            format!("bottompipe_{}", pair.id),
             bottompipe_img_obj,
             (self.pipe_width, self.pipe_height),
-            (self.canvas_size.0 + 100.0, gap_y + self.gap_size / 2.0),
+            (pair.x, center_y + size / 2.0),
             vec!["pipe".to_string(), "obstacle".to_string()],
-            (-3.0, 0.0),
+            (0.0, 0.0),
             (1.0, 1.0),
             0.0,
         );
         
-        canvas.add_game_object(format!("toppipe_{}", self.pipe_counter), toppipe);
-        canvas.add_game_object(format!("bottompipe_{}", self.pipe_counter), bottompipe);
-        
-        self.pipe_counter += 1;
+        canvas.add_game_object(format!("toppipe_{}", pair.id), toppipe);
+        canvas.add_game_object(format!("bottompipe_{}", pair.id), bottompipe);
     }
 
-    pub fn remove_offscreen_pipes(&mut self, canvas: &mut Canvas) {
-        let mut pipes_to_remove: Vec<String> = Vec::new();
-        
-        for i in 0..self.pipe_counter {
-            let toppipe_name = format!("toppipe_{}", i);
-            let bottompipe_name = format!("bottompipe_{}", i);
-            
-            if let Some(obj) = canvas.get_game_object(&toppipe_name) {
-                if obj.position.0 < -self.pipe_width - 50.0 {
-                    pipes_to_remove.push(toppipe_name);
-                }
-            }
-            
-            if let Some(obj) = canvas.get_game_object(&bottompipe_name) {
-                if obj.position.0 < -self.pipe_width - 50.0 {
-                    pipes_to_remove.push(bottompipe_name);
-                }
-            }
-        }
-        
-        for name in pipes_to_remove {
-            canvas.remove_game_object(&name);
+    /// Everything needed to put the course back exactly as it is now.
+    pub fn snapshot(&self) -> PipeSnapshot {
+        PipeSnapshot {
+            pipe_spawn_timer: self.pipe_spawn_timer,
+            pipe_counter: self.pipe_counter,
+            pairs: self.pairs.clone(),
+            rng: self.rng,
+            pickup_rng: self.pickup_rng,
+            pending: self.pending,
+            exhausted: self.exhausted,
+            finish_x: self.finish_x,
+            course_complete: self.course_complete,
+            generator: self.generator.save_state(),
         }
     }
 
-    pub fn reset(&mut self, canvas: &mut Canvas) {
-        for i in 0..self.pipe_counter {
-            canvas.remove_game_object(&format!("toppipe_{}", i));
-            canvas.remove_game_object(&format!("bottompipe_{}", i));
+    /// Puts the course back as it was in `snapshot`; the generator must be the one it was taken from.
+    pub fn restore(&mut self, snapshot: &PipeSnapshot) {
+        self.pipe_spawn_timer = snapshot.pipe_spawn_timer;
+        self.pipe_counter = snapshot.pipe_counter;
+        self.pairs = snapshot.pairs.clone();
+        self.rng = snapshot.rng;
+        self.pickup_rng = snapshot.pickup_rng;
+        self.pending = snapshot.pending;
+        self.exhausted = snapshot.exhausted;
+        self.finish_x = snapshot.finish_x;
+        self.course_complete = snapshot.course_complete;
+        self.generator.load_state(&snapshot.generator);
+    }
+
+    /// Asks the generator for the next pair and falls back to calmer gaps if the solver can't reach it.
+    fn next_spec(&mut self) -> Option<ObstacleSpec> {
+        let (rise, drop) = self.reach.limits(self.ticks(self.spawn_interval));
+        let ctx = GeneratorContext {
+            pair_index: self.pipe_counter,
+            previous: self.pairs.last(),
+            gap_size: self.gap_size,
+            bounds: self.gap_bounds(self.gap_size),
+            reach: (rise * REACH_MARGIN, drop * REACH_MARGIN),
+        };
+
+        let mut spec = self.generator.next(&ctx, &mut self.rng)?;
+        if !self.generator.is_authored() {
+            spec.delay *= self.spawn_interval / PIPE_SPAWN_INTERVAL;
         }
-        
+
+        let Some(previous) = self.pairs.last().filter(|_| !self.generator.is_authored()) else {
+            return Some(spec);
+        };
+
+        let (low, high) = ctx.reachable_range();
+        let fallbacks = [
+            spec,
+            ObstacleSpec { variant: PipeVariant::Static, ..spec },
+            ObstacleSpec { gap_y: spec.gap_y.clamp(low, high), variant: PipeVariant::Static, ..spec },
+        ];
+
+        let reachable = fallbacks.into_iter().find(|candidate| self.is_reachable(previous, candidate));
+        Some(reachable.unwrap_or(ObstacleSpec {
+            gap_y: previous.base_gap_y,
+            gap_size: previous.gap_size.max(spec.gap_size),
+            variant: PipeVariant::Static,
+            ..spec
+        }))
+    }
+
+    /// Whether the bird can get from `previous` to a pair laid out by `spec`: wherever their motion
+    /// puts the two gap centres while the bird is passing, the climb or drop between them has to
+    /// stay inside the precomputed limits for the time between them. This is an estimate; only
+    /// `reach::validate_course` proves a course passable.
+    fn is_reachable(&self, previous: &PipePair, spec: &ObstacleSpec) -> bool {
+        let spawn_x = self.canvas_size.0 + 100.0;
+        let candidate = PipePair {
+            id: self.pipe_counter,
+            x: spawn_x,
+            base_gap_y: spec.gap_y,
+            gap_size: spec.gap_size,
+            variant: spec.variant,
+            age: 0.0,
+        };
+
+        // Judge from the moment the previous pair spawned, so the answer never depends on
+        // how far it has scrolled since; seeded courses stay identical whatever the frame timing.
+        let previous = PipePair { x: spawn_x, age: 0.0, ..previous.clone() };
+        let (previous_top, previous_bottom) = self.passing_range(&previous);
+        let (candidate_top, candidate_bottom) = self.passing_range(&candidate);
+
+        let (rise, drop) = self.reach.limits(self.ticks(spec.delay));
+        previous_bottom - candidate_top <= rise * REACH_MARGIN && candidate_bottom - previous_top <= drop * REACH_MARGIN
+    }
+
+    /// Highest and lowest the gap centre of `pair`, just spawned, gets while it is over the bird.
+    fn passing_range(&self, pair: &PipePair) -> (f32, f32) {
+        let reaches = ((pair.x - self.solver.bird_x - self.solver.bird_size.0) / self.solver.scroll).max(0.0) as u32;
+        let leaves = ((pair.x + self.pipe_width - self.solver.bird_x) / self.solver.scroll).ceil() as u32;
+
+        (reaches..=leaves)
+            .map(|tick| pair.gap_after(tick as f32 * self.physics.tick, self.canvas_size.1).0)
+            .fold((f32::MAX, f32::MIN), |(top, bottom), center_y| (top.min(center_y), bottom.max(center_y)))
+    }
+
+    /// Drops pairs that have scrolled off screen and returns their ids.
+    fn remove_offscreen_pipes(&mut self) -> Vec<u32> {
+        let offscreen_x = -self.pipe_width - 50.0;
+        let removed = self.pairs.iter()
+            .filter(|pair| pair.x < offscreen_x)
+            .map(|pair| pair.id)
+            .collect();
+
+        self.pairs.retain(|pair| pair.x >= offscreen_x);
+        removed
+    }
+
+    /// Starts the course over; `clear` takes the old pipes off the canvas first.
+    pub fn reset(&mut self) {
+        self.pairs.clear();
         self.pipe_counter = 0;
         self.pipe_spawn_timer = 0.0;
+        self.generator.reset();
+        self.set_seed(self.seed);
+        self.pending = None;
+        self.exhausted = false;
+        self.finish_x = None;
+        self.course_complete = false;
     }
-}
\ No newline at end of file
+}
+
+/// Reflects `value` back and forth between `min` and `max` so drifting gaps bounce off the bounds.
+fn fold_into(value: f32, min: f32, max: f32) -> f32 {
+    let span = max - min;
+    if span <= 0.0 {
+        return min;
+    }
+
+    let t = (value - min).rem_euclid(span * 2.0);
+    if t <= span { min + t } else { max - (t - span) }
+}
@@ -0,0 +1,255 @@
+use prism::Context;
+use prism::canvas::{Image, ShapeType};
+use stork::{Canvas, GameObject};
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::pipe::PipeGap;
+use crate::ui;
+
+pub const PICKUP_SIZE: f32 = 30.0;
+const INDICATOR_SIZE: f32 = 24.0;
+const MAX_INDICATORS: usize = 8;
+const MAX_SHIELD_CHARGES: u32 = 3;
+const INVULNERABLE_TIME: f32 = 1.0;
+const SLOW_MOTION_SCALE: f32 = 0.5;
+const SHRINK_SCALE: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PowerUpKind {
+    Shield,
+    SlowMotion,
+    Shrink,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 3] = [PowerUpKind::Shield, PowerUpKind::SlowMotion, PowerUpKind::Shrink];
+
+    /// Seconds the effect lasts. Shields have no timer and last until they absorb a hit.
+    pub fn duration(&self) -> f32 {
+        match self {
+            PowerUpKind::Shield => 0.0,
+            PowerUpKind::SlowMotion => 5.0,
+            PowerUpKind::Shrink => 8.0,
+        }
+    }
+
+    fn color(&self) -> [u8; 3] {
+        match self {
+            PowerUpKind::Shield => [80, 160, 255],
+            PowerUpKind::SlowMotion => [190, 110, 255],
+            PowerUpKind::Shrink => [90, 220, 120],
+        }
+    }
+
+    pub fn icon(&self, size: u32) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+        let [r, g, b] = self.color();
+        ui::badge(size, 3.0, |_, _| image::Rgba([r, g, b, 255]))
+    }
+}
+
+/// Top-left corner of a pickup centred in `gap`.
+pub fn pickup_position(gap: &PipeGap) -> (f32, f32) {
+    (
+        gap.x + gap.width / 2.0 - PICKUP_SIZE / 2.0,
+        gap.center_y - PICKUP_SIZE / 2.0,
+    )
+}
+
+/// Saved effect timers, see `PowerUpManager::snapshot`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PowerUpSnapshot {
+    pub shield_charges: u32,
+    pub invulnerable_timer: f32,
+    pub slow_motion_timer: f32,
+    pub shrink_timer: f32,
+}
+
+/// Rolls power-ups for pipe gaps and tracks the active effects.
+///
+/// Stacking rules: each shield adds a charge up to `MAX_SHIELD_CHARGES`, slow-motion adds its
+/// duration to the time left (capped at twice the duration) and shrink restarts its timer.
+#[derive(Debug)]
+pub struct PowerUpManager {
+    pub spawn_chance: f32,
+    shield_charges: u32,
+    invulnerable_timer: f32,
+    slow_motion_timer: f32,
+    shrink_timer: f32,
+}
+
+impl PowerUpManager {
+    pub fn new(spawn_chance: f32) -> Self {
+        Self {
+            spawn_chance,
+            shield_charges: 0,
+            invulnerable_timer: 0.0,
+            slow_motion_timer: 0.0,
+            shrink_timer: 0.0,
+        }
+    }
+
+    pub fn shield_charges(&self) -> u32 {
+        self.shield_charges
+    }
+
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        match kind {
+            PowerUpKind::Shield => self.shield_charges > 0,
+            PowerUpKind::SlowMotion => self.slow_motion_timer > 0.0,
+            PowerUpKind::Shrink => self.shrink_timer > 0.0,
+        }
+    }
+
+    /// Whether a new gap gets a random pickup, and which.
+    pub fn roll(&self, rng: &mut impl Rng) -> Option<PowerUpKind> {
+        if rng.random_range(0.0..1.0) >= self.spawn_chance {
+            return None;
+        }
+
+        Some(PowerUpKind::ALL[rng.random_range(0..PowerUpKind::ALL.len())])
+    }
+
+    /// Runs every effect timer down by one tick of `dt` seconds.
+    pub fn tick_timers(&mut self, dt: f32) {
+        self.invulnerable_timer = (self.invulnerable_timer - dt).max(0.0);
+        self.slow_motion_timer = (self.slow_motion_timer - dt).max(0.0);
+        self.shrink_timer = (self.shrink_timer - dt).max(0.0);
+    }
+
+    /// Speed the world should scroll at relative to normal.
+    pub fn world_scale(&self) -> f32 {
+        if self.is_active(PowerUpKind::SlowMotion) { SLOW_MOTION_SCALE } else { 1.0 }
+    }
+
+    /// Size the bird should be drawn and hit at relative to normal.
+    pub fn bird_scale(&self) -> f32 {
+        if self.is_active(PowerUpKind::Shrink) { SHRINK_SCALE } else { 1.0 }
+    }
+
+    pub fn activate(&mut self, kind: PowerUpKind) {
+        match kind {
+            PowerUpKind::Shield => {
+                self.shield_charges = (self.shield_charges + 1).min(MAX_SHIELD_CHARGES);
+            }
+            PowerUpKind::SlowMotion => {
+                self.slow_motion_timer = (self.slow_motion_timer + kind.duration()).min(kind.duration() * 2.0);
+            }
+            PowerUpKind::Shrink => {
+                self.shrink_timer = kind.duration();
+            }
+        }
+    }
+
+    /// Returns true if a hit was absorbed, either by a shield charge or the grace period after one.
+    pub fn absorb_hit(&mut self) -> bool {
+        if self.invulnerable_timer > 0.0 {
+            return true;
+        }
+
+        if !self.is_active(PowerUpKind::Shield) {
+            return false;
+        }
+
+        self.shield_charges -= 1;
+        self.invulnerable_timer = INVULNERABLE_TIME;
+        true
+    }
+
+    pub fn update_display(&self, ctx: &mut Context, canvas: &mut Canvas) {
+        self.clear_display(canvas);
+
+        let mut indicators: Vec<(PowerUpKind, f32)> = (0..self.shield_charges())
+            .map(|_| (PowerUpKind::Shield, 1.0))
+            .collect();
+
+        if self.is_active(PowerUpKind::SlowMotion) {
+            indicators.push((PowerUpKind::SlowMotion, self.slow_motion_timer / (PowerUpKind::SlowMotion.duration() * 2.0)));
+        }
+
+        if self.is_active(PowerUpKind::Shrink) {
+            indicators.push((PowerUpKind::Shrink, self.shrink_timer / PowerUpKind::Shrink.duration()));
+        }
+
+        for (idx, (kind, remaining)) in indicators.into_iter().take(MAX_INDICATORS).enumerate() {
+            let x_pos = 20.0 + (idx as f32) * (INDICATOR_SIZE + 8.0);
+
+            let icon_obj = Image {
+                shape: ShapeType::Rectangle(0.0, (INDICATOR_SIZE, INDICATOR_SIZE), 0.0),
+                image: kind.icon(INDICATOR_SIZE as u32).into(),
+                color: None
+            };
+
+            let icon = GameObject::new_rect(
+                ctx,
+                format!("powerup_indicator_{}", idx),
+                icon_obj,
+                (INDICATOR_SIZE, INDICATOR_SIZE),
+                (x_pos, 20.0),
+                vec!["hud".to_string()],
+                (0.0, 0.0),
+                (1.0, 1.0),
+                0.0,
+            );
+
+            canvas.add_game_object(format!("powerup_indicator_{}", idx), icon);
+
+            if kind == PowerUpKind::Shield {
+                continue;
+            }
+
+            let [r, g, b] = kind.color();
+            let bar_width = (INDICATOR_SIZE * remaining).max(1.0);
+            let bar_obj = Image {
+                shape: ShapeType::Rectangle(0.0, (bar_width, 4.0), 0.0),
+                image: image::ImageBuffer::from_pixel(1, 1, image::Rgba([r, g, b, 255])).into(),
+                color: None
+            };
+
+            let bar = GameObject::new_rect(
+                ctx,
+                format!("powerup_bar_{}", idx),
+                bar_obj,
+                (bar_width, 4.0),
+                (x_pos, 20.0 + INDICATOR_SIZE + 4.0),
+                vec!["hud".to_string()],
+                (0.0, 0.0),
+                (1.0, 1.0),
+                0.0,
+            );
+
+            canvas.add_game_object(format!("powerup_bar_{}", idx), bar);
+        }
+    }
+
+    pub fn snapshot(&self) -> PowerUpSnapshot {
+        PowerUpSnapshot {
+            shield_charges: self.shield_charges,
+            invulnerable_timer: self.invulnerable_timer,
+            slow_motion_timer: self.slow_motion_timer,
+            shrink_timer: self.shrink_timer,
+        }
+    }
+
+    /// Puts the active effects back as they were.
+    pub fn restore(&mut self, snapshot: &PowerUpSnapshot) {
+        self.shield_charges = snapshot.shield_charges;
+        self.invulnerable_timer = snapshot.invulnerable_timer;
+        self.slow_motion_timer = snapshot.slow_motion_timer;
+        self.shrink_timer = snapshot.shrink_timer;
+    }
+
+    pub fn clear_display(&self, canvas: &mut Canvas) {
+        for idx in 0..MAX_INDICATORS {
+            canvas.remove_game_object(&format!("powerup_indicator_{}", idx));
+            canvas.remove_game_object(&format!("powerup_bar_{}", idx));
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.shield_charges = 0;
+        self.invulnerable_timer = 0.0;
+        self.slow_motion_timer = 0.0;
+        self.shrink_timer = 0.0;
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+
+use crate::snapshot::GameSnapshot;
+
+/// Ticks between checkpoints, about three seconds.
+const CHECKPOINT_INTERVAL: u64 = 188;
+const MAX_CHECKPOINTS: usize = 5;
+/// Ticks of history kept for rewinding, about five seconds.
+const HISTORY_TICKS: usize = 312;
+/// Ticks stepped back per "r" press; holding the key repeats it.
+const REWIND_STEP: usize = 4;
+/// A checkpoint this fresh is skipped, so restarting never drops the bird straight back into the crash.
+const CHECKPOINT_MIN_AGE: u64 = 60;
+
+/// Checkpoints and a rolling history of the current practice run.
+#[derive(Debug, Default)]
+pub struct Practice {
+    checkpoints: VecDeque<(u64, GameSnapshot)>,
+    history: VecDeque<(u64, GameSnapshot)>,
+    tick: u64,
+    pub crashed: bool,
+}
+
+impl Practice {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores the state after a played tick.
+    pub fn record(&mut self, snapshot: GameSnapshot) {
+        if self.tick.is_multiple_of(CHECKPOINT_INTERVAL) {
+            if self.checkpoints.len() == MAX_CHECKPOINTS {
+                self.checkpoints.pop_front();
+            }
+            self.checkpoints.push_back((self.tick, snapshot.clone()));
+        }
+
+        if self.history.len() == HISTORY_TICKS {
+            self.history.pop_front();
+        }
+        self.history.push_back((self.tick, snapshot));
+        self.tick += 1;
+    }
+
+    /// Steps back through the history, never past its oldest entry.
+    pub fn rewind(&mut self) -> Option<GameSnapshot> {
+        for _ in 0..REWIND_STEP {
+            if self.history.len() <= 1 {
+                break;
+            }
+            self.history.pop_back();
+        }
+
+        let (tick, snapshot) = self.history.back()?;
+        self.tick = tick + 1;
+        self.crashed = false;
+        Some(snapshot.clone())
+    }
+
+    /// The latest checkpoint that is not too close to now; later ones and the history are dropped.
+    pub fn checkpoint(&mut self) -> Option<GameSnapshot> {
+        while self.checkpoints.len() > 1
+            && self.checkpoints.back().is_some_and(|(tick, _)| tick + CHECKPOINT_MIN_AGE > self.tick)
+        {
+            self.checkpoints.pop_back();
+        }
+
+        let (tick, snapshot) = self.checkpoints.back()?;
+        self.tick = tick + 1;
+        self.history.retain(|(recorded, _)| recorded <= tick);
+        self.crashed = false;
+        Some(snapshot.clone())
+    }
+
+    pub fn reset(&mut self) {
+        self.checkpoints.clear();
+        self.history.clear();
+        self.tick = 0;
+        self.crashed = false;
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+
+use crate::physics::Physics;
+use crate::pipe::PipePair;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SolverConfig {
+    pub bird_x: f32,
+    pub bird_size: (f32, f32),
+    pub pipe_width: f32,
+    pub canvas_height: f32,
+    pub ground_y: f32,
+    /// Pixels the pipes scroll left each tick.
+    pub scroll: f32,
+    /// Fastest a player is expected to tap, in ticks between flaps.
+    pub min_flap_interval: u32,
+    /// Bucket sizes used to merge near-identical bird states.
+    pub y_resolution: f32,
+    pub momentum_resolution: f32,
+}
+
+impl SolverConfig {
+    pub fn for_canvas(canvas_size: (f32, f32), pipe_width: f32) -> Self {
+        Self {
+            bird_x: 200.0,
+            bird_size: (50.0, 35.0),
+            pipe_width,
+            canvas_height: canvas_size.1,
+            ground_y: canvas_size.1 - 112.0,
+            scroll: 3.0,
+            min_flap_interval: 6,
+            y_resolution: 3.0,
+            momentum_resolution: 0.5,
+        }
+    }
+}
+
+/// `pair` as it stands at `tick`; it scrolls left from `pair.x` and keeps ageing after that.
+#[derive(Debug, Clone)]
+pub struct ScheduledPair {
+    pub tick: u32,
+    pub pair: PipePair,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unreachable {
+    pub pair_index: usize,
+    pub tick: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BirdState {
+    y: f32,
+    momentum: f32,
+    cooldown: u32,
+}
+
+/// Largest climb and drop, in pixels, a bird starting level can manage in any number of ticks up
+/// to a limit, worked out once per physics and solver config so checking a new pair is a lookup
+/// rather than a search.
+///
+/// It's a heuristic, not a proof: it ignores the momentum the bird arrives with, which
+/// `validate_course` does track. The generation tests run that solver over seeded courses.
+#[derive(Debug, Clone)]
+pub struct ReachTable {
+    /// Climb and drop after `idx` ticks.
+    limits: Vec<(f32, f32)>,
+}
+
+impl ReachTable {
+    pub fn new(physics: &Physics, config: &SolverConfig, max_ticks: u32) -> Self {
+        let interval = config.min_flap_interval.max(1);
+        let mut limits = vec![(0.0, 0.0)];
+
+        let (mut climb_y, mut climb_momentum) = (0.0_f32, 0.0_f32);
+        let (mut fall_y, mut fall_momentum) = (0.0_f32, 0.0_f32);
+        let mut rise = 0.0_f32;
+        for tick in 0..max_ticks {
+            if tick.is_multiple_of(interval) {
+                climb_momentum = physics.flap(climb_momentum);
+            }
+            (climb_y, climb_momentum) = physics.step(climb_y, climb_momentum);
+            rise = rise.max(-climb_y);
+
+            (fall_y, fall_momentum) = physics.step(fall_y, fall_momentum);
+            limits.push((rise, fall_y.max(0.0)));
+        }
+
+        Self { limits }
+    }
+
+    /// Largest climb and drop in `ticks` ticks; past the end of the table the last entry stands,
+    /// which only ever under-reports.
+    pub fn limits(&self, ticks: u32) -> (f32, f32) {
+        self.limits[(ticks as usize).min(self.limits.len() - 1)]
+    }
+}
+
+/// Proves a course passable by tracking every bird state reachable from `start` tick by tick.
+///
+/// States are merged into buckets but each bucket keeps a real trajectory's exact values, so a
+/// non-empty set at the end means an actual flap sequence clears every pair.
+pub fn validate_course(
+    physics: &Physics,
+    config: &SolverConfig,
+    start: &[(f32, f32)],
+    course: &[ScheduledPair],
+) -> Result<(), Unreachable> {
+    let end_tick = course.iter()
+        .map(|scheduled| {
+            let distance = scheduled.pair.x + config.pipe_width - config.bird_x;
+            scheduled.tick + (distance / config.scroll).ceil().max(0.0) as u32 + 1
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut states: Vec<BirdState> = start.iter()
+        .map(|(y, momentum)| BirdState { y: *y, momentum: *momentum, cooldown: 0 })
+        .collect();
+
+    for tick in 0..end_tick {
+        let mut next: HashMap<(i32, i32, u32), BirdState> = HashMap::new();
+
+        for state in &states {
+            for flap in [false, true] {
+                if flap && state.cooldown > 0 {
+                    continue;
+                }
+
+                let momentum = if flap { physics.flap(state.momentum) } else { state.momentum };
+                let (mut y, mut momentum) = physics.step(state.y, momentum);
+
+                if y <= 0.0 {
+                    y = 0.0;
+                    momentum = 0.0;
+                }
+
+                if collides(physics, config, course, tick + 1, y) {
+                    continue;
+                }
+
+                let cooldown = if flap { config.min_flap_interval } else { state.cooldown.saturating_sub(1) };
+                let key = (
+                    (y / config.y_resolution).round() as i32,
+                    (momentum / config.momentum_resolution).round() as i32,
+                    cooldown,
+                );
+
+                next.entry(key).or_insert(BirdState { y, momentum, cooldown });
+            }
+        }
+
+        if next.is_empty() {
+            return Err(Unreachable {
+                pair_index: blocking_pair(config, course, tick + 1),
+                tick: tick + 1,
+            });
+        }
+
+        states = next.into_values().collect();
+    }
+
+    Ok(())
+}
+
+fn pair_x(config: &SolverConfig, scheduled: &ScheduledPair, tick: u32) -> Option<f32> {
+    if tick < scheduled.tick {
+        return None;
+    }
+
+    Some(scheduled.pair.x - config.scroll * (tick - scheduled.tick) as f32)
+}
+
+fn collides(physics: &Physics, config: &SolverConfig, course: &[ScheduledPair], tick: u32, y: f32) -> bool {
+    if y + config.bird_size.1 >= config.ground_y {
+        return true;
+    }
+
+    course.iter().any(|scheduled| {
+        let Some(x) = pair_x(config, scheduled, tick) else {
+            return false;
+        };
+
+        if x >= config.bird_x + config.bird_size.0 || x + config.pipe_width <= config.bird_x {
+            return false;
+        }
+
+        let extra_age = (tick - scheduled.tick) as f32 * physics.tick;
+        let (center_y, size) = scheduled.pair.gap_after(extra_age, config.canvas_height);
+
+        y < center_y - size / 2.0 || y + config.bird_size.1 > center_y + size / 2.0
+    })
+}
+
+fn blocking_pair(config: &SolverConfig, course: &[ScheduledPair], tick: u32) -> usize {
+    course.iter()
+        .position(|scheduled| {
+            pair_x(config, scheduled, tick).is_some_and(|x| x + config.pipe_width >= config.bird_x)
+        })
+        .unwrap_or(course.len().saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obstacle::GeneratorKind;
+    use crate::pipe::{PipeManager, PipeVariant, MIN_PASSABLE_GAP};
+    use crate::sim::{CANVAS_SIZE, GAP_SIZE, PIPE_HEIGHT, PIPE_WIDTH};
+    use crate::world::WorldSpeed;
+
+    const PAIRS: usize = 60;
+
+    fn solver() -> SolverConfig {
+        SolverConfig::for_canvas(CANVAS_SIZE, PIPE_WIDTH)
+    }
+
+    fn pair(x: f32, base_gap_y: f32, gap_size: f32) -> PipePair {
+        PipePair { id: 0, x, base_gap_y, gap_size, variant: PipeVariant::Static, age: 0.0 }
+    }
+
+    /// Starting states spread across the safe band of a gap, used to check a transition from any
+    /// line the player might be holding.
+    fn gap_band_states(config: &SolverConfig, center_y: f32, size: f32) -> Vec<(f32, f32)> {
+        let top = center_y - size / 2.0;
+        let bottom = center_y + size / 2.0 - config.bird_size.1;
+        let mut states = Vec::new();
+
+        let mut y = top;
+        while y <= bottom {
+            for momentum in [-6.0, 0.0, 1.5] {
+                states.push((y, momentum));
+            }
+            y += 8.0;
+        }
+
+        states
+    }
+
+    /// `pair` after scrolling for `ticks` more ticks at the default world speed.
+    fn advanced(pair: &PipePair, ticks: u32) -> PipePair {
+        PipePair {
+            x: pair.x - solver().scroll * ticks as f32,
+            age: pair.age + ticks as f32 * Physics::default().tick,
+            ..pair.clone()
+        }
+    }
+
+    /// The first `PAIRS` pairs of a seeded course, each as it stood on the tick it spawned.
+    fn generate(kind: GeneratorKind, seed: u64, gap_size: f32) -> Vec<ScheduledPair> {
+        let mut pipes = PipeManager::headless(PIPE_WIDTH, PIPE_HEIGHT, gap_size, CANVAS_SIZE);
+        pipes.set_seed(Some(seed));
+        pipes.set_generator(kind.build());
+
+        let world_speed = WorldSpeed::default();
+        let mut course = Vec::new();
+        let mut tick = 0;
+        while course.len() < PAIRS {
+            tick += 1;
+            if let Some(gap) = pipes.step(&world_speed).spawned {
+                let pair = pipes.pairs.iter().find(|pair| pair.id == gap.id).expect("Failed to find spawned pair");
+                course.push(ScheduledPair { tick, pair: PipePair { x: gap.x, age: 0.0, ..pair.clone() } });
+            }
+        }
+
+        course
+    }
+
+    #[test]
+    fn table_matches_direct_simulation() {
+        let physics = Physics::default();
+        let config = solver();
+        let table = ReachTable::new(&physics, &config, 200);
+
+        for ticks in [0_u32, 1, 6, 60, 125, 200] {
+            let interval = config.min_flap_interval;
+            let (mut y, mut momentum) = (0.0_f32, 0.0_f32);
+            let mut rise = 0.0_f32;
+            for tick in 0..ticks {
+                if tick.is_multiple_of(interval) {
+                    momentum = physics.flap(momentum);
+                }
+                (y, momentum) = physics.step(y, momentum);
+                rise = rise.max(-y);
+            }
+
+            assert_eq!(table.limits(ticks).0, rise);
+        }
+
+        assert_eq!(table.limits(1000), table.limits(200));
+        let (rise, drop) = table.limits(125);
+        assert!(rise > 0.0 && drop > 0.0);
+        assert!(table.limits(60).0 <= rise && table.limits(60).1 <= drop);
+    }
+
+    #[test]
+    fn finds_a_way_through_a_reachable_pair() {
+        let physics = Physics::default();
+        let config = solver();
+        let course = [ScheduledPair { tick: 0, pair: pair(600.0, 250.0, GAP_SIZE) }];
+
+        assert!(validate_course(&physics, &config, &[(300.0, 0.0)], &course).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_gap_too_far_to_climb() {
+        let physics = Physics::default();
+        let config = solver();
+        // The bird starts just above the ground and the gap is at the top, only a few ticks away.
+        let course = [ScheduledPair { tick: 0, pair: pair(config.bird_x + 60.0, 160.0, MIN_PASSABLE_GAP) }];
+        let result = validate_course(&physics, &config, &[(config.ground_y - 40.0, 0.0)], &course);
+
+        assert!(matches!(result, Err(Unreachable { pair_index: 0, .. })));
+    }
+
+    #[test]
+    fn long_seeded_courses_are_reachable_gap_by_gap() {
+        let physics = Physics::default();
+        // Coarser buckets keep this quick; every surviving state is still a real trajectory.
+        let config = SolverConfig { y_resolution: 6.0, momentum_resolution: 1.0, ..solver() };
+
+        for kind in GeneratorKind::ALL {
+            for gap_size in [GAP_SIZE, GAP_SIZE * 0.8] {
+                for seed in [1, 7, 20260519] {
+                    let course = generate(kind, seed, gap_size);
+
+                    for (idx, window) in course.windows(2).enumerate() {
+                        // Start as the previous pair reaches the bird; the open air before it
+                        // can't make the next pair any harder.
+                        let arrival = ((window[0].pair.x - config.bird_x - config.bird_size.0) / config.scroll).max(0.0) as u32;
+                        let previous = advanced(&window[0].pair, arrival);
+                        let (center_y, size) = previous.gap_at(CANVAS_SIZE.1);
+                        let start = gap_band_states(&config, center_y, size);
+
+                        let delay = window[1].tick - window[0].tick;
+                        let next = match delay.checked_sub(arrival) {
+                            Some(tick) => ScheduledPair { tick, pair: window[1].pair.clone() },
+                            None => ScheduledPair { tick: 0, pair: advanced(&window[1].pair, arrival - delay) },
+                        };
+                        let pairs = [ScheduledPair { tick: 0, pair: previous }, next];
+
+                        assert!(
+                            validate_course(&physics, &config, &start, &pairs).is_ok(),
+                            "{:?} seed {} gap {}: pair {} can't be reached from pair {}",
+                            kind, seed, gap_size, idx + 1, idx,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
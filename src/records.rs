@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::mode::ModeKind;
+use crate::storage::Storage;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyRecord {
+    /// Day of the last daily run, in days since the epoch.
+    pub day: Option<i64>,
+    /// Best score on `day`.
+    pub best: usize,
+    /// Consecutive days ending on `day` with at least one daily run.
+    pub streak: u32,
+}
+
+/// Best scores, kept apart per way of playing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Records {
+    /// Best endless score for each mode.
+    #[serde(default)]
+    pub best: BTreeMap<ModeKind, usize>,
+    pub daily: DailyRecord,
+}
+
+impl Records {
+    const KEY: &'static str = "FlappyRecords";
+
+    pub fn load(storage: &Storage) -> Self {
+        storage.get(Self::KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &Storage) {
+        storage.set(Self::KEY, self);
+    }
+
+    pub fn best(&self, mode: ModeKind) -> usize {
+        self.best.get(&mode).copied().unwrap_or(0)
+    }
+
+    /// Records an endless run in `mode` and returns true if it set a new best.
+    pub fn record_endless(&mut self, mode: ModeKind, score: usize) -> bool {
+        if score <= self.best(mode) {
+            return false;
+        }
+
+        self.best.insert(mode, score);
+        true
+    }
+
+    /// Records a daily run on `day`, extending the streak on the first run of a new day.
+    pub fn record_daily(&mut self, day: i64, score: usize) -> bool {
+        match self.daily.day {
+            Some(last) if last == day => {}
+            Some(last) if last == day - 1 => {
+                self.daily.streak += 1;
+                self.daily.best = 0;
+            }
+            _ => {
+                self.daily.streak = 1;
+                self.daily.best = 0;
+            }
+        }
+        self.daily.day = Some(day);
+
+        if score <= self.daily.best {
+            return false;
+        }
+
+        self.daily.best = score;
+        true
+    }
+
+    /// Today's best daily score.
+    pub fn daily_best(&self, today: i64) -> usize {
+        if self.daily.day == Some(today) { self.daily.best } else { 0 }
+    }
+
+    /// The streak as it stands today; it survives until a full day is missed.
+    pub fn daily_streak(&self, today: i64) -> u32 {
+        match self.daily.day {
+            Some(day) if day == today || day == today - 1 => self.daily.streak,
+            _ => 0,
+        }
+    }
+}
+
+/// One finished run, as kept in the run log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunEntry {
+    /// Day the run finished, in days since the epoch.
+    pub day: i64,
+    pub mode: ModeKind,
+    pub score: usize,
+    pub ticks: u64,
+}
+
+/// Every run of one mode on one day, folded into totals once the runs are old.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaySummary {
+    pub day: i64,
+    pub mode: ModeKind,
+    pub runs: u64,
+    pub best: usize,
+    pub total_score: u64,
+    pub total_ticks: u64,
+}
+
+impl DaySummary {
+    fn add(&mut self, run: &RunEntry) {
+        self.runs += 1;
+        self.best = self.best.max(run.score);
+        self.total_score += run.score as u64;
+        self.total_ticks += run.ticks;
+    }
+}
+
+/// Totals over the whole run log, served by the background service.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub runs: u64,
+    pub runs_today: u64,
+    pub best: BTreeMap<ModeKind, usize>,
+    pub average_score: f32,
+    /// Average run length in seconds.
+    pub average_seconds: f32,
+}
+
+/// Recent runs one by one and older ones as per-day summaries, see `RunLog::compact`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunLog {
+    pub runs: Vec<RunEntry>,
+    pub summaries: Vec<DaySummary>,
+}
+
+impl RunLog {
+    const KEY: &'static str = "FlappyRuns";
+
+    pub fn load(storage: &Storage) -> Self {
+        storage.get(Self::KEY).unwrap_or_default()
+    }
+
+    /// Adds `run` to the log kept in `storage`.
+    pub fn append(storage: &Storage, run: RunEntry) {
+        storage.update(Self::KEY, |log: &mut Self| log.runs.push(run));
+    }
+
+    /// Compacts the log kept in `storage` in one transaction, so runs appended meanwhile aren't lost.
+    /// Returns how many runs were folded, or `None` if the log couldn't be locked.
+    pub fn compact_stored(storage: &Storage, cutoff_day: i64) -> Option<usize> {
+        storage.update(Self::KEY, |log: &mut Self| log.compact(cutoff_day))
+    }
+
+    /// Folds runs from before `cutoff_day` into their day's summary and returns how many were folded.
+    pub fn compact(&mut self, cutoff_day: i64) -> usize {
+        let (old, recent): (Vec<RunEntry>, Vec<RunEntry>) = self.runs.drain(..).partition(|run| run.day < cutoff_day);
+        self.runs = recent;
+
+        for run in &old {
+            let summary = match self.summaries.iter_mut().find(|summary| summary.day == run.day && summary.mode == run.mode) {
+                Some(summary) => summary,
+                None => {
+                    self.summaries.push(DaySummary { day: run.day, mode: run.mode, runs: 0, best: 0, total_score: 0, total_ticks: 0 });
+                    self.summaries.last_mut().expect("Failed to add day summary")
+                }
+            };
+            summary.add(run);
+        }
+
+        self.summaries.sort_by_key(|summary| (summary.day, summary.mode));
+        old.len()
+    }
+
+    pub fn stats(&self, today: i64, tick: f32) -> RunStats {
+        let mut stats = RunStats::default();
+        let mut total_score = 0;
+        let mut total_ticks = 0;
+
+        for summary in &self.summaries {
+            stats.runs += summary.runs;
+            total_score += summary.total_score;
+            total_ticks += summary.total_ticks;
+            let best = stats.best.entry(summary.mode).or_default();
+            *best = (*best).max(summary.best);
+        }
+
+        for run in &self.runs {
+            stats.runs += 1;
+            total_score += run.score as u64;
+            total_ticks += run.ticks;
+            let best = stats.best.entry(run.mode).or_default();
+            *best = (*best).max(run.score);
+
+            if run.day == today {
+                stats.runs_today += 1;
+            }
+        }
+
+        if stats.runs > 0 {
+            stats.average_score = total_score as f32 / stats.runs as f32;
+            stats.average_seconds = total_ticks as f32 * tick / stats.runs as f32;
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mode::ModeKind;
+
+    #[test]
+    fn compaction_does_not_lose_runs_appended_meanwhile() {
+        let path = std::env::temp_dir().join(format!("orange-runlog-{}.db", std::process::id()));
+        let path = path.to_str().expect("Failed to build database path").to_string();
+        let _ = std::fs::remove_file(&path);
+        Storage::open_at(&path);
+
+        let appender = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let storage = Storage::open_at(&path);
+                for idx in 0..200 {
+                    RunLog::append(&storage, RunEntry { day: idx % 10, mode: ModeKind::Classic, score: 1, ticks: 60 });
+                }
+            })
+        };
+
+        let storage = Storage::open_at(&path);
+        for cutoff in 0..50 {
+            RunLog::compact_stored(&storage, cutoff % 10);
+        }
+        appender.join().expect("Failed to append runs");
+
+        let log = RunLog::load(&storage);
+        let folded: u64 = log.summaries.iter().map(|summary| summary.runs).sum();
+        assert_eq!(log.runs.len() as u64 + folded, 200);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
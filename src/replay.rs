@@ -0,0 +1,74 @@
+use serde::{Serialize, Deserialize};
+
+use crate::mode::ModeKind;
+use crate::obstacle::GeneratorKind;
+
+/// Bumped whenever `Replay` changes shape or what a tick means.
+pub const REPLAY_VERSION: u32 = 3;
+
+/// Everything needed to play a run again: the course and the ticks the player flapped on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub mode: ModeKind,
+    pub generator: GeneratorKind,
+    pub seed: u64,
+    /// Ticks the run lasted.
+    pub ticks: u64,
+    /// Ticks on which a flap was pressed, in order.
+    pub flaps: Vec<u64>,
+}
+
+/// Collects the flaps of the run in progress.
+#[derive(Debug, Default)]
+pub struct ReplayRecorder {
+    ticks: u64,
+    flaps: Vec<u64>,
+    /// The run didn't start from tick 0 in this session, so the flaps can't replay it.
+    incomplete: bool,
+}
+
+impl ReplayRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Called once per played tick.
+    pub fn record(&mut self, flapped: bool) {
+        if flapped {
+            self.flaps.push(self.ticks);
+        }
+        self.ticks += 1;
+    }
+
+    /// Ticks recorded so far this run.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn mark_incomplete(&mut self) {
+        self.incomplete = true;
+    }
+
+    /// The replay of the run so far, unless part of it wasn't recorded.
+    pub fn finish(&self, mode: ModeKind, generator: GeneratorKind, seed: u64) -> Option<Replay> {
+        if self.incomplete {
+            return None;
+        }
+
+        Some(Replay {
+            version: REPLAY_VERSION,
+            mode,
+            generator,
+            seed,
+            ticks: self.ticks,
+            flaps: self.flaps.clone(),
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+        self.flaps.clear();
+        self.incomplete = false;
+    }
+}
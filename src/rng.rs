@@ -0,0 +1,46 @@
+use rand::RngCore;
+use serde::{Serialize, Deserialize};
+
+/// Small seeded generator (SplitMix64) whose whole state is two integers.
+///
+/// Unlike `StdRng` its output is fixed across rand versions and platforms, so a seed always
+/// lays out the same course, and its state can be saved and restored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeededRng {
+    pub seed: u64,
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// A generator seeded from the thread RNG, for runs that don't need to repeat.
+    ///
+    /// The seed is kept to 32 bits so it fits in a challenge code.
+    pub fn from_entropy() -> Self {
+        Self::new(rand::rng().next_u32() as u64)
+    }
+}
+
+impl RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
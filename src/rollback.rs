@@ -0,0 +1,246 @@
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::physics::Physics;
+use crate::sim::{Ending, Simulation, StepEvents};
+use crate::snapshot::GameSnapshot;
+
+/// Ticks of history kept for rolling back; flaps older than this can no longer change the past.
+pub const MAX_ROLLBACK: u64 = 120;
+/// Ticks of past flaps repeated in every input message, so one lost message is covered by the next.
+pub const INPUT_REDUNDANCY: u64 = 30;
+
+/// A bird's vertical state at the start of a tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BirdFrame {
+    pub y: f32,
+    pub momentum: f32,
+}
+
+impl BirdFrame {
+    pub fn new(y: f32) -> Self {
+        Self { y, momentum: 0.0 }
+    }
+
+    /// Momentum this tick is stepped with, with the flap already applied.
+    pub fn launch(&self, physics: &Physics, flapped: bool) -> f32 {
+        if flapped { physics.flap(self.momentum) } else { self.momentum }
+    }
+
+    /// The frame one tick later, stopped at the ceiling.
+    pub fn step(&self, physics: &Physics, flapped: bool) -> Self {
+        let (y, momentum) = physics.step(self.y, self.launch(physics, flapped));
+
+        if y <= 0.0 {
+            Self { y: 0.0, momentum: 0.0 }
+        } else {
+            Self { y, momentum }
+        }
+    }
+}
+
+/// This side's flaps, kept long enough to repeat them in every outgoing message.
+#[derive(Debug, Default)]
+pub struct InputLog {
+    flaps: VecDeque<u64>,
+}
+
+impl InputLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, tick: u64) {
+        self.flaps.push_back(tick);
+    }
+
+    /// Flaps from the last `INPUT_REDUNDANCY` ticks up to `tick`.
+    pub fn recent(&mut self, tick: u64) -> Vec<u64> {
+        while self.flaps.front().is_some_and(|flap| flap + INPUT_REDUNDANCY < tick) {
+            self.flaps.pop_front();
+        }
+
+        self.flaps.iter().copied().collect()
+    }
+}
+
+/// Both players' flaps for an online round and the whole game at the start of every recent tick.
+///
+/// Ticks the opponent hasn't been heard from yet are predicted as "no flap", which is what a
+/// flappy bird does almost every tick. When a flap arrives for a tick already played, the game is
+/// put back as it was at that tick and played forward again up to now, so both birds, the scores
+/// and who is out always follow from the flaps actually made.
+#[derive(Debug, Default)]
+pub struct Rollback {
+    /// State at the start of each played tick, oldest first; `history[0]` is tick `base`.
+    history: VecDeque<GameSnapshot>,
+    base: u64,
+    local: BTreeSet<u64>,
+    remote: BTreeSet<u64>,
+    /// Every tick before this one has been heard from the opponent.
+    confirmed: u64,
+    /// Earliest played tick a late flap landed on, replayed from before the next tick is.
+    stale_from: Option<u64>,
+    pub rollbacks: u64,
+    pub resimulated: u64,
+    /// Flaps that arrived after falling out of the rollback window.
+    pub too_late: u64,
+}
+
+impl Rollback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The tick about to be played.
+    pub fn tick(&self) -> u64 {
+        self.base + self.history.len() as u64
+    }
+
+    /// Ticks played on a guess rather than the opponent's input.
+    pub fn predicted_ticks(&self) -> u64 {
+        self.tick().saturating_sub(self.confirmed)
+    }
+
+    /// Sets this side's input for the tick about to be played.
+    pub fn record_local(&mut self, flapped: bool) {
+        if flapped {
+            self.local.insert(self.tick());
+        }
+    }
+
+    /// Takes in the opponent's flaps up to `up_to`, marking the game for a replay if any land in
+    /// the played past.
+    pub fn receive(&mut self, up_to: u64, flaps: &[u64]) {
+        let now = self.tick();
+
+        for &flap in flaps {
+            if flap < self.base {
+                if flap >= self.confirmed {
+                    self.too_late += 1;
+                }
+                continue;
+            }
+
+            if self.remote.insert(flap) && flap < now {
+                self.stale_from = Some(self.stale_from.map_or(flap, |from| from.min(flap)));
+            }
+        }
+
+        self.confirmed = self.confirmed.max(up_to + 1);
+    }
+
+    /// Replays the game from the earliest tick a late flap changed, if any did.
+    pub fn catch_up(&mut self, sim: &mut Simulation) {
+        let Some(from) = self.stale_from.take() else {
+            return;
+        };
+
+        let now = self.tick();
+        let idx = (from - self.base) as usize;
+        sim.restore(&self.history[idx]);
+        self.history.truncate(idx);
+
+        for tick in from..now {
+            let start = sim.snapshot();
+            if sim.step(self.flaps(tick)).is_err() {
+                // The corrected past ends the round here, which the next `advance` settles.
+                sim.restore(&start);
+                break;
+            }
+            self.history.push_back(start);
+        }
+
+        self.rollbacks += 1;
+        self.resimulated += now - from;
+    }
+
+    /// Catches up on late flaps, then plays the next tick.
+    ///
+    /// A round that ends on a tick the opponent hasn't confirmed yet may still be undone by their
+    /// flaps, so it returns `Ok(None)` and stays on that tick until they are heard from.
+    pub fn advance(&mut self, sim: &mut Simulation) -> Result<Option<StepEvents>, Ending> {
+        self.catch_up(sim);
+
+        let tick = self.tick();
+        let start = sim.snapshot();
+
+        match sim.step(self.flaps(tick)) {
+            Ok(events) => {
+                self.push(start);
+                Ok(Some(events))
+            }
+            Err(_) if tick >= self.confirmed => {
+                sim.restore(&start);
+                Ok(None)
+            }
+            Err(ending) => Err(ending),
+        }
+    }
+
+    fn flaps(&self, tick: u64) -> [bool; 2] {
+        [self.local.contains(&tick), self.remote.contains(&tick)]
+    }
+
+    fn push(&mut self, snapshot: GameSnapshot) {
+        self.history.push_back(snapshot);
+
+        while self.history.len() as u64 > MAX_ROLLBACK {
+            self.history.pop_front();
+            self.base += 1;
+        }
+        self.local = self.local.split_off(&self.base);
+        self.remote = self.remote.split_off(&self.base);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mode::ModeKind;
+    use crate::net::START_Y;
+
+    const TICKS: u64 = 200;
+    /// Ticks the opponent's inputs take to arrive.
+    const LATENCY: u64 = 12;
+
+    fn versus() -> Simulation {
+        let mode = ModeKind::Versus;
+        let mut sim = Simulation::new(mode, mode.build().generator(), 11);
+        sim.add_rival(START_Y);
+        sim
+    }
+
+    #[test]
+    fn late_flaps_replay_the_whole_game() {
+        let local = |tick: u64| tick.is_multiple_of(18);
+        let remote: Vec<u64> = (0..TICKS).filter(|tick| tick % 23 == 5).collect();
+
+        let mut truth = versus();
+        let mut sim = versus();
+        let mut rollback = Rollback::new();
+
+        for tick in 0..TICKS {
+            truth.step([local(tick), remote.contains(&tick)]).expect("Failed to play the true run");
+
+            if let Some(up_to) = tick.checked_sub(LATENCY) {
+                let heard: Vec<u64> = remote.iter().copied().filter(|flap| *flap <= up_to).collect();
+                rollback.receive(up_to, &heard);
+            }
+
+            rollback.record_local(local(tick));
+            let events = rollback.advance(&mut sim).expect("Failed to play the predicted run");
+            assert!(events.is_some());
+        }
+
+        rollback.receive(TICKS - 1, &remote);
+        rollback.catch_up(&mut sim);
+
+        assert!(rollback.rollbacks > 0);
+        assert_eq!(rollback.too_late, 0);
+        assert_eq!(
+            serde_json::to_value(sim.snapshot()).expect("Failed to serialize snapshot"),
+            serde_json::to_value(truth.snapshot()).expect("Failed to serialize snapshot"),
+        );
+    }
+}
@@ -1,15 +1,48 @@
 use prism::Context;
 use prism::canvas::{Image, ShapeType};
 use stork::{Canvas, GameObject, Target, Location};
+use serde::{Serialize, Deserialize};
 use std::collections::HashSet;
 
+use crate::coin;
+
+/// Size of each digit and the gap between them, see `ScoreManager::draw_number`.
+#[derive(Debug, Clone, Copy)]
+pub struct DigitStyle {
+    pub width: f32,
+    pub height: f32,
+    pub spacing: f32,
+}
+
+impl DigitStyle {
+    pub const LARGE: DigitStyle = DigitStyle { width: 24.0, height: 38.0, spacing: 5.0 };
+    pub const SMALL: DigitStyle = DigitStyle { width: 16.0, height: 25.0, spacing: 3.0 };
+    pub const TINY: DigitStyle = DigitStyle { width: 12.0, height: 19.0, spacing: 2.0 };
+
+    /// Width `value` takes up when drawn.
+    pub fn text_width(&self, value: usize) -> f32 {
+        value.to_string().len() as f32 * (self.width + self.spacing) - self.spacing
+    }
+}
+
+/// Saved `ScoreManager` counters, see `ScoreManager::snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreSnapshot {
+    pub score: usize,
+    pub coins: usize,
+    pub scored_pipes: Vec<u32>,
+    pub bird_was_left_of_pipe: Vec<u32>,
+}
+
 #[derive(Debug)]
 pub struct ScoreManager {
     pub score: usize,
+    pub coins: usize,
     canvas_size: (f32, f32),
     scored_pipes: HashSet<u32>,
     bird_was_left_of_pipe: HashSet<u32>,
     number_images: Vec<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>,
+    coin_icon: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
 }
 
 impl ScoreManager {
@@ -58,56 +91,98 @@ impl ScoreManager {
 
         Self {
             score: 0,
+            coins: 0,
             scored_pipes: HashSet::new(),
             bird_was_left_of_pipe: HashSet::new(),
             number_images,
+            coin_icon: coin::coin_image(20),
             canvas_size,
         }
     }
 
-    pub fn check_score(&mut self, canvas: &Canvas, pipe_counter: u32, pipe_width: f32) {
-        if let Some(bird) = canvas.get_game_object("flappybird") {
-            let bird_center_x = bird.position.0 + 25.0; 
-            
-            for i in 0..pipe_counter {
-                if self.scored_pipes.contains(&i) {
-                    continue;
-                }
-                
-                let pipe_name = format!("toppipe_{}", i);
-                if let Some(pipe) = canvas.get_game_object(&pipe_name) {
-                    let pipe_center_x = pipe.position.0 + pipe_width / 2.0;
-                    
-                    if bird_center_x < pipe_center_x {
-                        self.bird_was_left_of_pipe.insert(i);
-                    }
-
-                    else if self.bird_was_left_of_pipe.contains(&i) && bird_center_x > pipe_center_x {
-                        self.score += 1;
-                        self.scored_pipes.insert(i);
-                        self.bird_was_left_of_pipe.remove(&i);
-                        println!("Score: {}", self.score);
-                    }
-                }
+    pub fn with_number_images(mut self, number_images: Vec<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>) -> Self {
+        assert_eq!(number_images.len(), 10, "Score display needs one image per digit");
+        self.number_images = number_images;
+        self
+    }
+
+    /// Scores every pipe, given as its id and left edge, whose middle `bird_center_x` has just
+    /// passed, and returns how many that was.
+    pub fn score_pipes(&mut self, bird_center_x: f32, pipes: impl IntoIterator<Item = (u32, f32)>, pipe_width: f32) -> usize {
+        let mut passed = 0;
+
+        for (i, pipe_x) in pipes {
+            if self.scored_pipes.contains(&i) {
+                continue;
+            }
+
+            let pipe_center_x = pipe_x + pipe_width / 2.0;
+
+            if bird_center_x < pipe_center_x {
+                self.bird_was_left_of_pipe.insert(i);
+            }
+
+            else if self.bird_was_left_of_pipe.contains(&i) && bird_center_x > pipe_center_x {
+                self.score += 1;
+                self.scored_pipes.insert(i);
+                self.bird_was_left_of_pipe.remove(&i);
+                passed += 1;
             }
         }
+
+        passed
     }
 
     pub fn update_display(&mut self, ctx: &mut Context, canvas: &mut Canvas) {
-        let score_str = self.score.to_string();
-        let digit_width = 24.0;
-        let digit_height = 38.0;
-        let spacing = 5.0;
-        let total_width = (score_str.len() as f32) * (digit_width + spacing) - spacing;
-        let start_x = self.canvas_size.0 - total_width - 20.0;
-        let start_y = 20.0;
+        let right_x = self.canvas_size.0 - 20.0;
+        self.draw_number(ctx, canvas, "score_digit", self.score, (right_x, 20.0), DigitStyle::LARGE);
+
+        let coin_width = self.draw_number(ctx, canvas, "coin_digit", self.coins, (right_x, 68.0), DigitStyle::SMALL);
+        let icon_size = 20.0;
+
+        let icon_obj = Image {
+            shape: ShapeType::Rectangle(0.0, (icon_size, icon_size), 0.0),
+            image: self.coin_icon.clone().into(),
+            color: None
+        };
+
+        let icon = GameObject::new_rect(
+            ctx,
+            "coin_icon".to_string(),
+            icon_obj,
+            (icon_size, icon_size),
+            (right_x - coin_width - icon_size - 6.0, 70.0),
+            vec!["score".to_string()],
+            (0.0, 0.0),
+            (1.0, 1.0),
+            0.0,
+        );
+
+        canvas.remove_game_object("coin_icon");
+        canvas.add_game_object("coin_icon".to_string(), icon);
+    }
+
+    /// Draws `value` right-aligned to `right_x` and returns the width it took up.
+    pub fn draw_number(
+        &self,
+        ctx: &mut Context,
+        canvas: &mut Canvas,
+        prefix: &str,
+        value: usize,
+        (right_x, start_y): (f32, f32),
+        style: DigitStyle,
+    ) -> f32 {
+        let DigitStyle { width: digit_width, height: digit_height, spacing } = style;
+        let value_str = value.to_string();
+        let total_width = style.text_width(value);
+        let start_x = right_x - total_width;
         
         for idx in 0..10 {
-            let name = format!("score_digit_{}", idx);
+            let name = format!("{}_{}", prefix, idx);
             canvas.remove_game_object(&name);
         }
         
-        for (idx, digit_char) in score_str.chars().enumerate() {
+        for (idx, digit_char) in value_str.chars().enumerate() {
             let digit = digit_char.to_digit(10).unwrap() as usize;
             let digit_image = self.number_images[digit].clone();
             
@@ -121,7 +196,7 @@ impl ScoreManager {
             
             let digit_obj = GameObject::new_rect(
                 ctx,
-                format!("score_digit_{}", idx),
+                format!("{}_{}", prefix, idx),
                 img_obj,
                 (digit_width, digit_height),
                 (x_pos, start_y),
@@ -131,17 +206,49 @@ impl ScoreManager {
                 0.0,
             );
             
-            canvas.add_game_object(format!("score_digit_{}", idx), digit_obj);
+            canvas.add_game_object(format!("{}_{}", prefix, idx), digit_obj);
         }
+
+        total_width
     }
 
-    pub fn reset(&mut self, ctx: &mut Context, canvas: &mut Canvas) {
+    /// Pipe ids come out sorted, so equal counters always save the same way.
+    pub fn snapshot(&self) -> ScoreSnapshot {
+        let sorted = |ids: &HashSet<u32>| {
+            let mut ids: Vec<u32> = ids.iter().copied().collect();
+            ids.sort_unstable();
+            ids
+        };
+
+        ScoreSnapshot {
+            score: self.score,
+            coins: self.coins,
+            scored_pipes: sorted(&self.scored_pipes),
+            bird_was_left_of_pipe: sorted(&self.bird_was_left_of_pipe),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &ScoreSnapshot) {
+        self.score = snapshot.score;
+        self.coins = snapshot.coins;
+        self.scored_pipes = snapshot.scored_pipes.iter().copied().collect();
+        self.bird_was_left_of_pipe = snapshot.bird_was_left_of_pipe.iter().copied().collect();
+    }
+
+    /// Zeroes the counters without touching the display.
+    pub fn clear(&mut self) {
         self.score = 0;
+        self.coins = 0;
         self.scored_pipes.clear();
         self.bird_was_left_of_pipe.clear();
+    }
+
+    pub fn reset(&mut self, ctx: &mut Context, canvas: &mut Canvas) {
+        self.clear();
         
         for idx in 0..10 {
             canvas.remove_game_object(&format!("score_digit_{}", idx));
+            canvas.remove_game_object(&format!("coin_digit_{}", idx));
         }
         
         self.update_display(ctx, canvas);
@@ -0,0 +1,124 @@
+use prism::Context;
+use prism::canvas::{Image, ShapeType};
+use stork::{Canvas, GameObject};
+
+#[derive(Debug)]
+pub struct ScrollingStrip {
+    name: String,
+    image: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
+    tile_size: (f32, f32),
+    y: f32,
+    tags: Vec<String>,
+    parallax: f32,
+    tile_count: usize,
+}
+
+impl ScrollingStrip {
+    pub fn new(
+        name: &str,
+        image: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
+        tile_size: (f32, f32),
+        y: f32,
+        tags: Vec<String>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            image,
+            tile_size,
+            y,
+            tags,
+            parallax: 1.0,
+            tile_count: 0,
+        }
+    }
+
+    /// Scales the world scroll speed for this strip, < 1.0 for background layers.
+    pub fn with_parallax(mut self, parallax: f32) -> Self {
+        self.parallax = parallax;
+        self
+    }
+
+    pub fn tiles_for_width(&self, width: f32) -> usize {
+        (width / self.tile_size.0).ceil() as usize + 1
+    }
+
+    pub fn total_width(&self) -> f32 {
+        self.tile_size.0 * self.tile_count as f32
+    }
+
+    fn tile_name(&self, idx: usize) -> String {
+        format!("{}{}", self.name, idx + 1)
+    }
+
+    pub fn spawn(&mut self, ctx: &mut Context, canvas: &mut Canvas, width: f32) {
+        self.despawn(canvas);
+        self.tile_count = self.tiles_for_width(width);
+
+        for idx in 0..self.tile_count {
+            let img_obj = Image {
+                shape: ShapeType::Rectangle(0.0, self.tile_size, 0.0),
+                image: self.image.clone().into(),
+                color: None
+            };
+
+            let tile = GameObject::new(
+                ctx,
+                self.tile_name(idx),
+                img_obj,
+                self.tile_size.0.max(self.tile_size.1),
+                (self.tile_size.0 * idx as f32, self.y),
+                self.tags.clone(),
+                (0.0, 0.0),
+                (1.0, 1.0),
+                0.0,
+            );
+
+            canvas.add_game_object(self.tile_name(idx), tile);
+        }
+    }
+
+    pub fn despawn(&mut self, canvas: &mut Canvas) {
+        for idx in 0..self.tile_count {
+            canvas.remove_game_object(&self.tile_name(idx));
+        }
+
+        self.tile_count = 0;
+    }
+
+    pub fn update(&mut self, canvas: &mut Canvas, speed: f32) {
+        let step = speed * self.parallax;
+        let total_width = self.total_width();
+
+        for idx in 0..self.tile_count {
+            if let Some(tile) = canvas.get_game_object_mut(&self.tile_name(idx)) {
+                tile.position.0 += step;
+                if tile.position.0 < -self.tile_size.0 {
+                    tile.position.0 += total_width;
+                }
+            }
+        }
+    }
+}
+
+/// Ground tile turned upside down and cut down to `height`, for the strip along the ceiling.
+pub fn ceiling_image(ground: &image::ImageBuffer<image::Rgba<u8>, Vec<u8>>, height: u32) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+    let height = height.min(ground.height());
+    let flipped = image::imageops::flip_vertical(ground);
+
+    image::imageops::crop_imm(&flipped, 0, ground.height() - height, ground.width(), height).to_image()
+}
+
+/// A tileable row of rolling hills, drawn behind the pipes.
+pub fn hills_image(width: u32, height: u32) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+    image::ImageBuffer::from_fn(width, height, |x, y| {
+        // Whole waves across the tile, so the edges meet when tiled.
+        let t = x as f32 / width as f32 * std::f32::consts::TAU;
+        let ridge = height as f32 * (0.45 + 0.2 * t.sin() + 0.1 * (3.0 * t).sin());
+
+        if (y as f32) < height as f32 - ridge {
+            image::Rgba([0, 0, 0, 0])
+        } else {
+            image::Rgba([110, 190, 120, 200])
+        }
+    })
+}
@@ -0,0 +1,134 @@
+use prism::Context;
+use stork::Canvas;
+
+use crate::atlas::Atlas;
+use crate::coin;
+use crate::score::{DigitStyle, ScoreManager};
+use crate::skin::{BirdSkin, PipeSkin, BackgroundSkin, Skin, Cosmetics};
+use crate::ui::{solid, Overlay};
+use crate::wallet::Wallet;
+
+const THUMB_SIZE: f32 = 96.0;
+const COLUMN_X: [f32; 3] = [200.0, 360.0, 520.0];
+const ROW_Y: [f32; 3] = [120.0, 270.0, 420.0];
+
+#[derive(Debug)]
+struct ShopEntry {
+    skin: Skin,
+    thumbnail: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
+    position: (f32, f32),
+}
+
+/// Between-runs screen for buying and equipping skins, driven with "a"/"d" and "e".
+#[derive(Debug)]
+pub struct Shop {
+    entries: Vec<ShopEntry>,
+    cursor: usize,
+    overlay: Overlay,
+}
+
+impl Shop {
+    pub fn new(atlas: &Atlas) -> Self {
+        let mut entries = Vec::new();
+
+        for (col, bird) in BirdSkin::ALL.iter().enumerate() {
+            entries.push(ShopEntry {
+                skin: Skin::Bird(*bird),
+                thumbnail: bird.preview(),
+                position: (COLUMN_X[col], ROW_Y[0]),
+            });
+        }
+
+        for (col, pipes) in PipeSkin::ALL.iter().enumerate() {
+            let (_, bottompipe) = pipes.images(atlas);
+            let side = bottompipe.width();
+            entries.push(ShopEntry {
+                skin: Skin::Pipes(*pipes),
+                thumbnail: image::imageops::crop_imm(&bottompipe, 0, 0, side, side).to_image(),
+                position: (COLUMN_X[col], ROW_Y[1]),
+            });
+        }
+
+        for (col, background) in BackgroundSkin::ALL.iter().enumerate() {
+            entries.push(ShopEntry {
+                skin: Skin::Background(*background),
+                thumbnail: background.image(),
+                position: (COLUMN_X[col], ROW_Y[2]),
+            });
+        }
+
+        Self {
+            entries,
+            cursor: 0,
+            overlay: Overlay::new("shop"),
+        }
+    }
+
+    /// Handles a shop key and returns the skin that was just equipped, if any.
+    pub fn handle_key(&mut self, key: &str, wallet: &mut Wallet, cosmetics: &mut Cosmetics) -> Option<Skin> {
+        match key {
+            "a" => {
+                self.cursor = (self.cursor + self.entries.len() - 1) % self.entries.len();
+                None
+            }
+            "d" => {
+                self.cursor = (self.cursor + 1) % self.entries.len();
+                None
+            }
+            "e" => {
+                let skin = self.entries[self.cursor].skin;
+
+                if !cosmetics.owns(skin) {
+                    if !wallet.spend(skin.cost()) {
+                        println!("> Not enough coins, {:?} costs {}", skin, skin.cost());
+                        return None;
+                    }
+
+                    cosmetics.owned.push(skin);
+                }
+
+                cosmetics.equip(skin);
+                Some(skin)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas, wallet: &Wallet, cosmetics: &Cosmetics, score_manager: &ScoreManager) {
+        self.clear(canvas);
+
+        self.overlay.add(ctx, canvas, "shop_overlay", solid([0, 0, 0, 170]), (0.0, 0.0), (2000.0, 2000.0));
+
+        let (cursor_x, cursor_y) = self.entries[self.cursor].position;
+        self.overlay.add(ctx, canvas, "shop_cursor", solid([255, 255, 255, 255]), (cursor_x - 4.0, cursor_y - 4.0), (THUMB_SIZE + 8.0, THUMB_SIZE + 8.0));
+
+        for idx in 0..self.entries.len() {
+            let entry = &self.entries[idx];
+            let (x, y) = entry.position;
+            let skin = entry.skin;
+            let thumbnail = entry.thumbnail.clone();
+
+            self.overlay.add(ctx, canvas, &format!("shop_item_{}", idx), thumbnail, (x, y), (THUMB_SIZE, THUMB_SIZE));
+
+            if cosmetics.is_equipped(skin) {
+                self.overlay.add(ctx, canvas, &format!("shop_marker_{}", idx), solid([90, 220, 120, 255]), (x, y + THUMB_SIZE + 8.0), (THUMB_SIZE, 6.0));
+            } else if cosmetics.owns(skin) {
+                self.overlay.add(ctx, canvas, &format!("shop_marker_{}", idx), solid([160, 160, 160, 255]), (x, y + THUMB_SIZE + 8.0), (THUMB_SIZE, 6.0));
+            } else {
+                let prefix = format!("shop_price_{}", idx);
+                score_manager.draw_number(ctx, canvas, &prefix, skin.cost() as usize, (x + THUMB_SIZE, y + THUMB_SIZE + 6.0), DigitStyle::TINY);
+                self.overlay.track_digits(&prefix);
+                self.overlay.add(ctx, canvas, &format!("shop_price_icon_{}", idx), coin::coin_image(16), (x, y + THUMB_SIZE + 8.0), (16.0, 16.0));
+            }
+        }
+
+        let right_x = COLUMN_X[2] + THUMB_SIZE;
+        let wallet_width = score_manager.draw_number(ctx, canvas, "shop_wallet", wallet.coins as usize, (right_x, 40.0), DigitStyle::LARGE);
+        self.overlay.track_digits("shop_wallet");
+        self.overlay.add(ctx, canvas, "shop_wallet_icon", coin::coin_image(28), (right_x - wallet_width - 36.0, 45.0), (28.0, 28.0));
+    }
+
+    pub fn clear(&mut self, canvas: &mut Canvas) {
+        self.overlay.clear(canvas);
+    }
+}
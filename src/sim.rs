@@ -0,0 +1,544 @@
+use serde::{Serialize, Deserialize};
+
+use crate::coin::{CoinManager, COIN_SIZE};
+use crate::mode::{GameMode, Hit, ModeKind, Verdict};
+use crate::obstacle::{GeneratorKind, Pickup};
+use crate::physics::Physics;
+use crate::pickup::LivePickup;
+use crate::pipe::{PipeGap, PipeManager, PipeStep, PIPE_SPAWN_INTERVAL};
+use crate::powerup::{self, PowerUpManager};
+use crate::reach::SolverConfig;
+use crate::rollback::BirdFrame;
+use crate::score::{ScoreManager, ScoreSnapshot};
+use crate::snapshot::GameSnapshot;
+use crate::world::WorldSpeed;
+
+pub const CANVAS_SIZE: (f32, f32) = (800.0, 600.0);
+pub const PIPE_WIDTH: f32 = 100.0 * 0.5;
+pub const PIPE_HEIGHT: f32 = 800.0;
+pub const GAP_SIZE: f32 = 220.0;
+pub const POWERUP_CHANCE: f32 = 0.25;
+pub const COIN_CHANCE: f32 = 0.6;
+pub const BIRD_START: (f32, f32) = (200.0, 300.0);
+
+/// The numbers a headless run is played with, so a tuning change can be tried out before it ships.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    /// Gap size before the mode adjusts it.
+    pub gap_size: f32,
+    /// Seconds between generated pairs.
+    pub spawn_interval: f32,
+    pub pipe_width: f32,
+    /// Chance of a power-up in each new gap.
+    pub powerup_chance: f32,
+    /// Chance of coins through and leading up to each new gap.
+    pub coin_chance: f32,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            gap_size: GAP_SIZE,
+            spawn_interval: PIPE_SPAWN_INTERVAL,
+            pipe_width: PIPE_WIDTH,
+            powerup_chance: POWERUP_CHANCE,
+            coin_chance: COIN_CHANCE,
+        }
+    }
+}
+
+/// What ended a headless run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Ending {
+    PipeTop,
+    PipeBottom,
+    Ground,
+    Ceiling,
+    TimeUp,
+    CourseComplete,
+}
+
+impl Ending {
+    pub const ALL: [Ending; 6] = [
+        Ending::PipeTop,
+        Ending::PipeBottom,
+        Ending::Ground,
+        Ending::Ceiling,
+        Ending::TimeUp,
+        Ending::CourseComplete,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ending::PipeTop => "pipe_top",
+            Ending::PipeBottom => "pipe_bottom",
+            Ending::Ground => "ground",
+            Ending::Ceiling => "ceiling",
+            Ending::TimeUp => "time_up",
+            Ending::CourseComplete => "course_complete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+/// Saved `Flyer` state, see `Simulation::snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlyerSnapshot {
+    pub bird: BirdFrame,
+    pub score: ScoreSnapshot,
+    pub out: bool,
+    pub ticks_alive: u64,
+    pub in_contact: bool,
+}
+
+/// One bird on the course: where it is, what it has scored and whether it is still flying.
+#[derive(Debug)]
+pub struct Flyer {
+    pub bird: BirdFrame,
+    pub score: ScoreManager,
+    pub out: bool,
+    pub ticks_alive: u64,
+    start_y: f32,
+    in_contact: bool,
+}
+
+impl Flyer {
+    fn new(start_y: f32) -> Self {
+        Self {
+            bird: BirdFrame::new(start_y),
+            score: ScoreManager::new(CANVAS_SIZE),
+            out: false,
+            ticks_alive: 0,
+            start_y,
+            in_contact: false,
+        }
+    }
+
+    pub fn snapshot(&self) -> FlyerSnapshot {
+        FlyerSnapshot {
+            bird: self.bird,
+            score: self.score.snapshot(),
+            out: self.out,
+            ticks_alive: self.ticks_alive,
+            in_contact: self.in_contact,
+        }
+    }
+
+    fn restore(&mut self, snapshot: &FlyerSnapshot) {
+        self.bird = snapshot.bird;
+        self.score.restore(&snapshot.score);
+        self.out = snapshot.out;
+        self.ticks_alive = snapshot.ticks_alive;
+        self.in_contact = snapshot.in_contact;
+    }
+
+    fn reset(&mut self) {
+        self.bird = BirdFrame::new(self.start_y);
+        self.score.clear();
+        self.out = false;
+        self.ticks_alive = 0;
+        self.in_contact = false;
+    }
+}
+
+/// What one `Simulation::step` changed that the screen has to catch up with.
+#[derive(Debug, Default)]
+pub struct StepEvents {
+    pub pipes: PipeStep,
+    /// Pairs player one and player two flew past.
+    pub scored: [usize; 2],
+    /// Birds knocked out of a versus round that goes on without them.
+    pub eliminated: Vec<(Player, Ending)>,
+}
+
+/// The rules of a run, tick by tick, with no canvas: `Game` steps one and draws what it shows,
+/// and replays and bot runs step the same thing headlessly.
+#[derive(Debug)]
+pub struct Simulation {
+    physics: Physics,
+    solver: SolverConfig,
+    /// Gap size before the mode adjusts it.
+    base_gap_size: f32,
+    pub mode: Box<dyn GameMode>,
+    pub pipes: PipeManager,
+    pub powerups: PowerUpManager,
+    coins: CoinManager,
+    pickups: Vec<LivePickup>,
+    pickup_counter: u32,
+    pub world_speed: WorldSpeed,
+    pub player: Flyer,
+    /// Player two, in versus rounds.
+    pub rival: Option<Flyer>,
+    tick: u64,
+}
+
+impl Simulation {
+    pub fn new(mode: ModeKind, generator: GeneratorKind, seed: u64) -> Self {
+        Self::with_tuning(mode, generator, seed, &Tuning::default())
+    }
+
+    pub fn with_tuning(mode: ModeKind, generator: GeneratorKind, seed: u64, tuning: &Tuning) -> Self {
+        let physics = Physics::default();
+        let solver = SolverConfig::for_canvas(CANVAS_SIZE, tuning.pipe_width);
+
+        let mut pipes = PipeManager::headless(tuning.pipe_width, PIPE_HEIGHT, tuning.gap_size, CANVAS_SIZE)
+            .with_physics(physics, solver)
+            .with_spawn_interval(tuning.spawn_interval);
+        pipes.set_seed(Some(seed));
+        pipes.set_generator(generator.build());
+
+        Self::with_pipes(mode, pipes, tuning)
+    }
+
+    /// A run on `pipes`, whatever course they are set to, with the mode's gap size applied.
+    pub fn with_pipes(mode: ModeKind, pipes: PipeManager, tuning: &Tuning) -> Self {
+        let mut sim = Self {
+            physics: Physics::default(),
+            solver: SolverConfig::for_canvas(CANVAS_SIZE, tuning.pipe_width),
+            base_gap_size: tuning.gap_size,
+            mode: mode.build(),
+            pipes,
+            powerups: PowerUpManager::new(tuning.powerup_chance),
+            coins: CoinManager::new(tuning.coin_chance),
+            pickups: Vec::new(),
+            pickup_counter: 0,
+            world_speed: WorldSpeed::default(),
+            player: Flyer::new(BIRD_START.1),
+            rival: None,
+            tick: 0,
+        };
+        sim.set_mode(mode);
+        sim
+    }
+
+    /// Switches the rules; call `reset` before playing on.
+    pub fn set_mode(&mut self, mode: ModeKind) {
+        self.mode = mode.build();
+        self.pipes.gap_size = self.mode.gap_size(self.base_gap_size);
+    }
+
+    /// Brings in player two, starting at `start_y`.
+    pub fn add_rival(&mut self, start_y: f32) {
+        self.rival = Some(Flyer::new(start_y));
+    }
+
+    pub fn remove_rival(&mut self) {
+        self.rival = None;
+    }
+
+    /// Starts the course and every bird over.
+    pub fn reset(&mut self) {
+        self.pipes.reset();
+        self.player.reset();
+        if let Some(rival) = &mut self.rival {
+            rival.reset();
+        }
+        self.powerups.reset();
+        self.coins.reset();
+        self.pickups.clear();
+        self.pickup_counter = 0;
+        self.mode.reset();
+        self.world_speed.scale = 1.0;
+        self.tick = 0;
+    }
+
+    /// Everything needed to put the run back exactly as it is now.
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            tick: self.tick,
+            player: self.player.snapshot(),
+            rival: self.rival.as_ref().map(Flyer::snapshot),
+            pipes: self.pipes.snapshot(),
+            powerups: self.powerups.snapshot(),
+            pickups: self.pickups.clone(),
+            pickup_counter: self.pickup_counter,
+            coins: self.coins.clone(),
+            mode_state: self.mode.save_state(),
+            world_scale: self.world_speed.scale,
+        }
+    }
+
+    /// Puts the run back as it was in `snapshot`; the mode, course and players must be the ones it
+    /// was taken with.
+    pub fn restore(&mut self, snapshot: &GameSnapshot) {
+        self.tick = snapshot.tick;
+        self.player.restore(&snapshot.player);
+        if let (Some(rival), Some(saved)) = (&mut self.rival, &snapshot.rival) {
+            rival.restore(saved);
+        }
+        self.pipes.restore(&snapshot.pipes);
+        self.powerups.restore(&snapshot.powerups);
+        self.pickups = snapshot.pickups.clone();
+        self.pickup_counter = snapshot.pickup_counter;
+        self.coins = snapshot.coins.clone();
+        self.mode.load_state(&snapshot.mode_state);
+        self.world_speed.scale = snapshot.world_scale;
+    }
+
+    /// Player one's score.
+    pub fn score(&self) -> usize {
+        self.player.score.score
+    }
+
+    /// Ticks played so far.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Player one's bird.
+    pub fn bird(&self) -> BirdFrame {
+        self.player.bird
+    }
+
+    pub fn pipes(&self) -> &PipeManager {
+        &self.pipes
+    }
+
+    pub fn pickups(&self) -> &[LivePickup] {
+        &self.pickups
+    }
+
+    pub fn physics(&self) -> &Physics {
+        &self.physics
+    }
+
+    /// Left edge of the birds, which never move sideways.
+    pub fn bird_x(&self) -> f32 {
+        self.solver.bird_x
+    }
+
+    /// Player one's hitbox, smaller while shrunk.
+    pub fn bird_size(&self) -> (f32, f32) {
+        let scale = self.powerups.bird_scale();
+        (self.solver.bird_size.0 * scale, self.solver.bird_size.1 * scale)
+    }
+
+    /// The higher score wins a versus round; on a tie the bird that stayed up longer does, and
+    /// otherwise it's a draw.
+    pub fn winner(&self) -> Option<Player> {
+        let rival = self.rival.as_ref()?;
+
+        let order = self.player.score.score.cmp(&rival.score.score)
+            .then(self.player.ticks_alive.cmp(&rival.ticks_alive));
+
+        match order {
+            std::cmp::Ordering::Greater => Some(Player::One),
+            std::cmp::Ordering::Less => Some(Player::Two),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Plays one tick with player one's and player two's flaps, or returns what ended the run
+    /// before it could be.
+    pub fn step(&mut self, flaps: [bool; 2]) -> Result<StepEvents, Ending> {
+        let mut events = StepEvents::default();
+        self.check_collisions(&mut events)?;
+
+        // Modes only end a run from `update` when their clock runs out.
+        if self.mode.update(self.physics.tick) != Verdict::Continue {
+            return Err(Ending::TimeUp);
+        }
+
+        self.update_pickups();
+        events.pipes = self.pipes.step(&self.world_speed);
+        if let Some(gap) = events.pipes.spawned {
+            self.on_gap_spawned(&gap);
+        }
+        self.coins.advance(&self.world_speed);
+        if self.pipes.is_course_complete() {
+            return Err(Ending::CourseComplete);
+        }
+
+        let bird_center_x = self.solver.bird_x + 25.0;
+        let pipes: Vec<(u32, f32)> = self.pipes.pairs.iter().map(|pair| (pair.id, pair.x)).collect();
+        let pipe_width = self.pipes.pipe_width;
+
+        let flyers = std::iter::once(&mut self.player).chain(self.rival.as_mut());
+        for ((flyer, flapped), scored) in flyers.zip(flaps).zip(&mut events.scored) {
+            if flyer.out {
+                continue;
+            }
+
+            *scored = flyer.score.score_pipes(bird_center_x, pipes.iter().copied(), pipe_width);
+            flyer.bird = flyer.bird.step(&self.physics, flapped);
+            flyer.ticks_alive += 1;
+        }
+
+        self.tick += 1;
+        Ok(events)
+    }
+
+    /// What a bird of `size` with its top at `top` is touching, told apart more finely than `Hit`
+    /// so runs can be broken down by it.
+    fn classify_hit(&self, top: f32, (width, height): (f32, f32)) -> Option<Ending> {
+        let left = self.solver.bird_x;
+
+        if top + height >= self.solver.ground_y {
+            return Some(Ending::Ground);
+        }
+
+        for pair in &self.pipes.pairs {
+            if pair.x >= left + width || pair.x + self.pipes.pipe_width <= left {
+                continue;
+            }
+
+            let (center_y, size) = self.pipes.resolve_gap(pair);
+            if top < center_y - size / 2.0 {
+                return Some(Ending::PipeTop);
+            }
+            if top + height > center_y + size / 2.0 {
+                return Some(Ending::PipeBottom);
+            }
+        }
+
+        if top <= 0.0 && self.mode.deadly_ceiling() {
+            return Some(Ending::Ceiling);
+        }
+
+        None
+    }
+
+    fn check_collisions(&mut self, events: &mut StepEvents) -> Result<(), Ending> {
+        let mut last_out = None;
+
+        if !self.player.out {
+            if let Err(ending) = self.check_player() {
+                if self.rival.is_none() {
+                    return Err(ending);
+                }
+
+                self.player.out = true;
+                events.eliminated.push((Player::One, ending));
+                last_out = Some(ending);
+            }
+        }
+
+        let rival_hit = self.rival.as_ref()
+            .filter(|rival| !rival.out)
+            .and_then(|rival| self.classify_hit(rival.bird.y, self.solver.bird_size));
+
+        if let (Some(ending), Some(rival)) = (rival_hit, &mut self.rival) {
+            if self.mode.on_hit(hit_of(ending), true) != Verdict::Continue {
+                rival.out = true;
+                events.eliminated.push((Player::Two, ending));
+                last_out = Some(ending);
+            }
+        }
+
+        let all_out = self.player.out && self.rival.as_ref().is_none_or(|rival| rival.out);
+        match last_out {
+            Some(ending) if all_out => Err(ending),
+            _ => Ok(()),
+        }
+    }
+
+    /// Judges player one's hit, if any; shields and modes that survive it let the run go on.
+    fn check_player(&mut self) -> Result<(), Ending> {
+        let size = self.bird_size();
+        let ending = self.classify_hit(self.player.bird.y, size);
+
+        let new_contact = ending.is_some() && !self.player.in_contact;
+        self.player.in_contact = ending.is_some();
+
+        let Some(ending) = ending else {
+            return Ok(());
+        };
+
+        if self.powerups.absorb_hit() {
+            return Ok(());
+        }
+
+        let hit = hit_of(ending);
+        match self.mode.on_hit(hit, new_contact) {
+            Verdict::Continue => {
+                // Modes that survive the ground stand the bird on it.
+                if hit == Hit::Ground {
+                    let bird = &mut self.player.bird;
+                    bird.y = bird.y.min(self.solver.ground_y - size.1);
+                    bird.momentum = bird.momentum.min(0.0);
+                }
+                Ok(())
+            }
+            _ => Err(ending),
+        }
+    }
+
+    /// Moves the pickups on, collects the ones player one touches and applies the active effects.
+    fn update_pickups(&mut self) {
+        self.powerups.tick_timers(self.physics.tick);
+
+        let top_left = (self.solver.bird_x, self.player.bird.y);
+        let size = self.bird_size();
+        let collecting = !self.player.out;
+        let speed = self.world_speed.value();
+        let mut collected = Vec::new();
+
+        self.pickups.retain_mut(|pickup| {
+            if collecting && pickup.touches(top_left, size) {
+                collected.push(pickup.kind);
+                return false;
+            }
+
+            pickup.position.0 += speed;
+            pickup.position.0 >= -pickup.size()
+        });
+
+        for kind in collected {
+            match kind {
+                Pickup::Coin => self.player.score.coins += 1,
+                Pickup::PowerUp(kind) => self.powerups.activate(kind),
+            }
+        }
+
+        self.world_speed.scale = self.powerups.world_scale();
+    }
+
+    fn on_gap_spawned(&mut self, gap: &PipeGap) {
+        // Only player one can collect pickups, so versus rounds go without.
+        if self.rival.is_some() {
+            return;
+        }
+
+        let gap_center = (gap.x + gap.width / 2.0, gap.center_y);
+        match gap.pickup {
+            Some(Pickup::Coin) => self.add_coin(gap_center),
+            Some(kind @ Pickup::PowerUp(_)) => self.add_pickup(powerup::pickup_position(gap), kind),
+            None => {}
+        }
+
+        if self.pipes.generator().is_authored() {
+            return;
+        }
+
+        if let Some(kind) = self.powerups.roll(self.pipes.pickup_rng()) {
+            self.add_pickup(powerup::pickup_position(gap), Pickup::PowerUp(kind));
+        }
+
+        for center in self.coins.on_gap_spawned(gap, self.pipes.pickup_rng()) {
+            self.add_coin(center);
+        }
+    }
+
+    fn add_coin(&mut self, (x, y): (f32, f32)) {
+        self.add_pickup((x - COIN_SIZE / 2.0, y - COIN_SIZE / 2.0), Pickup::Coin);
+    }
+
+    fn add_pickup(&mut self, position: (f32, f32), kind: Pickup) {
+        self.pickups.push(LivePickup { id: self.pickup_counter, position, kind });
+        self.pickup_counter += 1;
+    }
+}
+
+fn hit_of(ending: Ending) -> Hit {
+    match ending {
+        Ending::Ground => Hit::Ground,
+        Ending::Ceiling => Hit::Ceiling,
+        _ => Hit::Pipe,
+    }
+}
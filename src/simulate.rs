@@ -0,0 +1,225 @@
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::bot::Bot;
+use crate::mode::ModeKind;
+use crate::physics::Physics;
+use crate::sim::{Ending, Simulation, Tuning};
+
+/// Bot seeds are kept apart from course seeds so the same bot can fly several courses.
+const BOT_SALT: u64 = 0x424F_5453_4545_4421;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+/// Settings for `orange simulate`.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub runs: u64,
+    pub skill: f32,
+    /// Course seeds, cycled through if there are fewer than `runs`.
+    pub seeds: std::ops::Range<u64>,
+    pub mode: ModeKind,
+    pub tuning: Tuning,
+    /// Runs still going after this many ticks are stopped and counted as survived.
+    pub max_ticks: u64,
+    pub format: Format,
+}
+
+impl Config {
+    pub const USAGE: &'static str = "Usage: orange simulate [--runs n] [--skill 0..1] [--seeds first..end] [--mode name] [--config tuning.json] [--max-ticks n] [--format json|csv]";
+
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut config = Self {
+            runs: 100,
+            skill: 0.8,
+            seeds: 0..0,
+            mode: ModeKind::Classic,
+            tuning: Tuning::default(),
+            max_ticks: 3_750,
+            format: Format::Json,
+        };
+        let mut runs = None;
+
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let value = args.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+            let invalid = |e: &dyn std::fmt::Display| format!("Invalid value for {}: {}", flag, e);
+
+            match flag.as_str() {
+                "--runs" => runs = Some(value.parse().map_err(|e| invalid(&e))?),
+                "--skill" => config.skill = value.parse().map_err(|e| invalid(&e))?,
+                "--seeds" => config.seeds = parse_range(value).ok_or_else(|| invalid(&"expected first..end"))?,
+                "--mode" => config.mode = ModeKind::from_name(value).ok_or_else(|| invalid(&"unknown mode"))?,
+                "--config" => {
+                    let json = std::fs::read_to_string(value).map_err(|e| invalid(&e))?;
+                    config.tuning = serde_json::from_str(&json).map_err(|e| invalid(&e))?;
+                }
+                "--max-ticks" => config.max_ticks = value.parse().map_err(|e| invalid(&e))?,
+                "--format" => config.format = match value.as_str() {
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    _ => return Err(invalid(&"expected json or csv")),
+                },
+                _ => return Err(format!("Unknown flag {}", flag)),
+            }
+        }
+
+        // A seed range on its own decides how many runs there are.
+        config.runs = runs.unwrap_or(if config.seeds.is_empty() { config.runs } else { config.seeds.end - config.seeds.start });
+        if config.seeds.is_empty() {
+            config.seeds = 0..config.runs;
+        }
+
+        if config.runs == 0 || config.max_ticks == 0 {
+            return Err("--runs and --max-ticks must be at least 1".to_string());
+        }
+
+        if !(0.0..=1.0).contains(&config.skill) {
+            return Err("--skill must be between 0 and 1".to_string());
+        }
+
+        let tuning = &config.tuning;
+        if tuning.gap_size <= 0.0 || tuning.spawn_interval <= 0.0 || tuning.pipe_width <= 0.0 {
+            return Err("gap_size, spawn_interval and pipe_width must be above 0".to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+fn parse_range(value: &str) -> Option<std::ops::Range<u64>> {
+    let (first, end) = value.split_once("..")?;
+    Some(first.parse().ok()?..end.parse().ok()?)
+}
+
+/// How one run went.
+#[derive(Debug, Clone, Copy)]
+pub struct RunResult {
+    pub seed: u64,
+    pub score: usize,
+    pub ticks: u64,
+    /// `None` if the run was stopped at `Config::max_ticks`.
+    pub ending: Option<Ending>,
+}
+
+/// Totals over every run of a batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub runs: u64,
+    pub mode: ModeKind,
+    pub skill: f32,
+    pub tuning: Tuning,
+    /// Runs per final score.
+    pub scores: BTreeMap<usize, u64>,
+    pub mean_score: f32,
+    pub median_score: usize,
+    /// Runs per way of ending, with "survived" for runs stopped at the tick limit.
+    pub endings: BTreeMap<String, u64>,
+    pub average_ticks: f32,
+    pub average_seconds: f32,
+}
+
+/// Plays one run with a bot at the wheel.
+pub fn play(config: &Config, seed: u64) -> RunResult {
+    let mut sim = Simulation::with_tuning(config.mode, config.mode.build().generator(), seed, &config.tuning);
+    let mut bot = Bot::new(config.skill, seed ^ BOT_SALT);
+
+    let mut ending = None;
+    while sim.tick() < config.max_ticks {
+        let flapped = bot.decide(&sim);
+        if let Err(end) = sim.step([flapped, false]) {
+            ending = Some(end);
+            break;
+        }
+    }
+
+    RunResult { seed, score: sim.score(), ticks: sim.tick(), ending }
+}
+
+/// Plays every run of `config`, spread over as many threads as there are cores.
+pub fn run(config: &Config) -> Report {
+    let span = config.seeds.end - config.seeds.start;
+    let seeds: Vec<u64> = (0..config.runs).map(|idx| config.seeds.start + idx % span).collect();
+
+    let threads = std::thread::available_parallelism().map_or(1, |count| count.get());
+    let chunk_size = seeds.len().div_ceil(threads);
+    let results: Vec<RunResult> = std::thread::scope(|scope| {
+        let handles: Vec<_> = seeds.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(|seed| play(config, *seed)).collect::<Vec<_>>()))
+            .collect();
+
+        handles.into_iter()
+            .flat_map(|handle| handle.join().expect("Failed to join simulation thread"))
+            .collect()
+    });
+
+    let mut scores = BTreeMap::new();
+    let mut endings: BTreeMap<String, u64> = Ending::ALL.iter().map(|ending| (ending.name().to_string(), 0)).collect();
+    endings.insert("survived".to_string(), 0);
+
+    for result in &results {
+        *scores.entry(result.score).or_default() += 1;
+        let ending = result.ending.map_or("survived", |ending| ending.name());
+        *endings.entry(ending.to_string()).or_default() += 1;
+    }
+
+    let mut sorted: Vec<usize> = results.iter().map(|result| result.score).collect();
+    sorted.sort_unstable();
+
+    let runs = results.len() as f32;
+    let average_ticks = results.iter().map(|result| result.ticks).sum::<u64>() as f32 / runs;
+
+    Report {
+        runs: config.runs,
+        mode: config.mode,
+        skill: config.skill,
+        tuning: config.tuning,
+        scores,
+        mean_score: sorted.iter().sum::<usize>() as f32 / runs,
+        median_score: sorted[sorted.len() / 2],
+        endings,
+        average_ticks,
+        average_seconds: average_ticks * Physics::default().tick,
+    }
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize report")
+    }
+
+    /// One `section,key,value` row per number, so the whole report fits one table.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("section,key,value\n");
+        let mut row = |section: &str, key: &dyn std::fmt::Display, value: &dyn std::fmt::Display| {
+            writeln!(csv, "{},{},{}", section, key, value).expect("Failed to write row");
+        };
+
+        row("summary", &"runs", &self.runs);
+        row("summary", &"mode", &self.mode.name());
+        row("summary", &"skill", &self.skill);
+        row("summary", &"mean_score", &self.mean_score);
+        row("summary", &"median_score", &self.median_score);
+        row("summary", &"average_ticks", &self.average_ticks);
+        row("summary", &"average_seconds", &self.average_seconds);
+        row("tuning", &"gap_size", &self.tuning.gap_size);
+        row("tuning", &"spawn_interval", &self.tuning.spawn_interval);
+        row("tuning", &"pipe_width", &self.tuning.pipe_width);
+        row("tuning", &"powerup_chance", &self.tuning.powerup_chance);
+
+        for (score, count) in &self.scores {
+            row("score", score, count);
+        }
+
+        for (ending, count) in &self.endings {
+            row("ending", ending, count);
+        }
+
+        csv
+    }
+}
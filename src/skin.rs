@@ -0,0 +1,238 @@
+use serde::{Serialize, Deserialize};
+
+use crate::animation::{Animator, AnimationClip};
+use crate::atlas::Atlas;
+use crate::bird::TiltConfig;
+use crate::storage::Storage;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BirdSkin {
+    Classic,
+    Player,
+    Alien,
+}
+
+impl BirdSkin {
+    pub const ALL: [BirdSkin; 3] = [BirdSkin::Classic, BirdSkin::Player, BirdSkin::Alien];
+
+    pub fn animator(&self, atlas: &Atlas) -> Animator {
+        match self {
+            BirdSkin::Classic => {
+                let flappybird_gif_bytes = include_bytes!("../assets/flappybird.gif");
+                let flap_clip = AnimationClip::from_gif(flappybird_gif_bytes, 24.0, false);
+                let first_frame = flap_clip.frames()[0].clone();
+                let mut animator = Animator::new("idle", AnimationClip::from_gif(flappybird_gif_bytes, 8.0, true))
+                    .with_clip("flap", flap_clip)
+                    .with_clip("glide", AnimationClip::single(first_frame.clone()))
+                    .with_clip("dead", AnimationClip::single(first_frame));
+
+                for state in ["idle", "flap", "glide", "dead"] {
+                    if let Some(clip) = atlas.clip(&format!("bird_{}", state)) {
+                        animator = animator.with_clip(state, clip);
+                    }
+                }
+
+                animator
+            }
+            BirdSkin::Player | BirdSkin::Alien => {
+                let frame = self.preview();
+                Animator::new("idle", AnimationClip::single(frame.clone()))
+                    .with_clip("flap", AnimationClip::single(frame.clone()))
+                    .with_clip("glide", AnimationClip::single(frame.clone()))
+                    .with_clip("dead", AnimationClip::single(frame))
+            }
+        }
+    }
+
+    /// How far the bird noses up and down; the heavier skins dive sooner and steeper.
+    pub fn tilt(&self) -> TiltConfig {
+        match self {
+            BirdSkin::Classic => TiltConfig::default(),
+            BirdSkin::Player => TiltConfig {
+                max_up_angle: 15.0,
+                max_down_angle: 60.0,
+                dive_velocity: 6.0,
+                dive_angle: 50.0,
+                smoothing: 0.15,
+            },
+            BirdSkin::Alien => TiltConfig {
+                max_up_angle: 35.0,
+                max_down_angle: 90.0,
+                dive_velocity: 3.0,
+                dive_angle: 40.0,
+                smoothing: 0.3,
+            },
+        }
+    }
+
+    pub fn preview(&self) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+        let bytes: &[u8] = match self {
+            BirdSkin::Classic => include_bytes!("../assets/flappybird.png"),
+            BirdSkin::Player => include_bytes!("../assets/player.png"),
+            BirdSkin::Alien => include_bytes!("../assets/alien.png"),
+        };
+
+        image::load_from_memory(bytes)
+            .expect("Failed to load bird skin image")
+            .to_rgba8()
+    }
+}
+
+/// Top and bottom pipe images.
+pub type PipeImages = (image::ImageBuffer<image::Rgba<u8>, Vec<u8>>, image::ImageBuffer<image::Rgba<u8>, Vec<u8>>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PipeSkin {
+    Classic,
+    Brick,
+    Frost,
+}
+
+impl PipeSkin {
+    pub const ALL: [PipeSkin; 3] = [PipeSkin::Classic, PipeSkin::Brick, PipeSkin::Frost];
+
+    /// Returns the top and bottom pipe images, recoloured from the atlas's classic ones when it has them.
+    pub fn images(&self, atlas: &Atlas) -> PipeImages {
+        let (mut toppipe_image, mut bottompipe_image) = match (atlas.frame("toppipe"), atlas.frame("bottompipe")) {
+            (Some(toppipe), Some(bottompipe)) => (toppipe.clone(), bottompipe.clone()),
+            _ => {
+                let toppipe_bytes = include_bytes!("../assets/toppipe.png");
+                let toppipe_img = image::load_from_memory(toppipe_bytes)
+                    .expect("Failed to load toppipe image");
+
+                let bottompipe_bytes = include_bytes!("../assets/bottompipe.png");
+                let bottompipe_img = image::load_from_memory(bottompipe_bytes)
+                    .expect("Failed to load bottompipe image");
+
+                (toppipe_img.to_rgba8(), bottompipe_img.to_rgba8())
+            }
+        };
+
+        for pixel in toppipe_image.pixels_mut().chain(bottompipe_image.pixels_mut()) {
+            let [r, g, b, a] = pixel.0;
+            pixel.0 = match self {
+                PipeSkin::Classic => [r, g, b, a],
+                PipeSkin::Brick => [g, r, b, a],
+                PipeSkin::Frost => [r, b, g, a],
+            };
+        }
+
+        (toppipe_image, bottompipe_image)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BackgroundSkin {
+    Day,
+    Night,
+    City,
+}
+
+impl BackgroundSkin {
+    pub const ALL: [BackgroundSkin; 3] = [BackgroundSkin::Day, BackgroundSkin::Night, BackgroundSkin::City];
+
+    pub fn image(&self) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+        let bytes: &[u8] = match self {
+            BackgroundSkin::Day | BackgroundSkin::Night => include_bytes!("../assets/bg.png"),
+            BackgroundSkin::City => include_bytes!("../assets/background.png"),
+        };
+
+        let mut image = image::load_from_memory(bytes)
+            .expect("Failed to load background image")
+            .to_rgba8();
+
+        if *self == BackgroundSkin::Night {
+            for pixel in image.pixels_mut() {
+                let [r, g, b, a] = pixel.0;
+                pixel.0 = [
+                    (r as f32 * 0.35) as u8,
+                    (g as f32 * 0.4) as u8,
+                    (b as f32 * 0.55 + 40.0).min(255.0) as u8,
+                    a,
+                ];
+            }
+        }
+
+        image
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Skin {
+    Bird(BirdSkin),
+    Pipes(PipeSkin),
+    Background(BackgroundSkin),
+}
+
+impl Skin {
+    /// Coin price, free skins cost nothing.
+    pub fn cost(&self) -> u64 {
+        match self {
+            Skin::Bird(BirdSkin::Classic) => 0,
+            Skin::Bird(BirdSkin::Player) => 50,
+            Skin::Bird(BirdSkin::Alien) => 120,
+            Skin::Pipes(PipeSkin::Classic) => 0,
+            Skin::Pipes(PipeSkin::Brick) => 40,
+            Skin::Pipes(PipeSkin::Frost) => 40,
+            Skin::Background(BackgroundSkin::Day) => 0,
+            Skin::Background(BackgroundSkin::Night) => 60,
+            Skin::Background(BackgroundSkin::City) => 80,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Loadout {
+    pub bird: BirdSkin,
+    pub pipes: PipeSkin,
+    pub background: BackgroundSkin,
+}
+
+impl Default for Loadout {
+    fn default() -> Self {
+        Self {
+            bird: BirdSkin::Classic,
+            pipes: PipeSkin::Classic,
+            background: BackgroundSkin::Day,
+        }
+    }
+}
+
+/// Equipped loadout and owned skins, persisted across sessions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cosmetics {
+    pub equipped: Loadout,
+    pub owned: Vec<Skin>,
+}
+
+impl Cosmetics {
+    const KEY: &'static str = "FlappyCosmetics";
+
+    pub fn load(storage: &Storage) -> Self {
+        storage.get(Self::KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &Storage) {
+        storage.set(Self::KEY, self);
+    }
+
+    pub fn owns(&self, skin: Skin) -> bool {
+        skin.cost() == 0 || self.owned.contains(&skin)
+    }
+
+    pub fn is_equipped(&self, skin: Skin) -> bool {
+        match skin {
+            Skin::Bird(bird) => self.equipped.bird == bird,
+            Skin::Pipes(pipes) => self.equipped.pipes == pipes,
+            Skin::Background(background) => self.equipped.background == background,
+        }
+    }
+
+    pub fn equip(&mut self, skin: Skin) {
+        match skin {
+            Skin::Bird(bird) => self.equipped.bird = bird,
+            Skin::Pipes(pipes) => self.equipped.pipes = pipes,
+            Skin::Background(background) => self.equipped.background = background,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Serialize, Deserialize};
+
+use crate::Course;
+use crate::coin::CoinManager;
+use crate::mode::ModeKind;
+use crate::pickup::LivePickup;
+use crate::pipe::PipeSnapshot;
+use crate::powerup::PowerUpSnapshot;
+use crate::sim::FlyerSnapshot;
+use crate::storage::Storage;
+
+/// Bumped whenever `SavedRun` or anything inside it changes shape.
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+/// A run frozen at one tick: enough to put the birds, the pipes, the pickups and the scores back
+/// exactly, see `Simulation::snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub tick: u64,
+    pub player: FlyerSnapshot,
+    pub rival: Option<FlyerSnapshot>,
+    pub pipes: PipeSnapshot,
+    pub powerups: PowerUpSnapshot,
+    pub pickups: Vec<LivePickup>,
+    pub pickup_counter: u32,
+    pub coins: CoinManager,
+    pub mode_state: serde_json::Value,
+    pub world_scale: f32,
+}
+
+/// An unfinished run kept in storage so it survives the app being closed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedRun {
+    pub version: u32,
+    pub mode: ModeKind,
+    pub course: Course,
+    pub snapshot: GameSnapshot,
+}
+
+impl SavedRun {
+    const KEY: &'static str = "FlappySavedRun";
+
+    pub fn new(mode: ModeKind, course: Course, snapshot: GameSnapshot) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            mode,
+            course,
+            snapshot,
+        }
+    }
+
+    /// The saved run, if there is one this version of the game can read.
+    pub fn load(storage: &Storage) -> Option<Self> {
+        let value: serde_json::Value = storage.get(Self::KEY)?;
+        let version = value.get("version").and_then(|version| version.as_u64());
+
+        if version != Some(SNAPSHOT_VERSION as u64) {
+            println!("> Saved run is version {:?}, expected {}, discarding it", version, SNAPSHOT_VERSION);
+            Self::clear(storage);
+            return None;
+        }
+
+        serde_json::from_value(value)
+            .map_err(|e| println!("> Failed to read saved run: {}", e))
+            .ok()
+    }
+
+    pub fn save(&self, storage: &Storage) {
+        storage.set(Self::KEY, self);
+    }
+
+    pub fn clear(storage: &Storage) {
+        storage.remove(Self::KEY);
+    }
+}
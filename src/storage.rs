@@ -0,0 +1,125 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Key-value store backed by the `kvs` table in `cache.db`.
+///
+/// Values are stored as hex encoded JSON, the same layout the table already uses.
+#[derive(Debug)]
+pub struct Storage {
+    #[cfg(not(target_arch = "wasm32"))]
+    connection: Option<rusqlite::Connection>,
+}
+
+impl Storage {
+    pub const PATH: &'static str = "cache.db";
+
+    pub fn open() -> Self {
+        Self::open_at(Self::PATH)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_at(path: &str) -> Self {
+        let connection = rusqlite::Connection::open(path)
+            .and_then(|connection| {
+                connection.execute("CREATE TABLE IF NOT EXISTS kvs(key TEXT NOT NULL UNIQUE, value TEXT)", [])?;
+                // The game and `orange background` share the file, so wait out the other's writes.
+                connection.busy_timeout(std::time::Duration::from_secs(5))?;
+                Ok(connection)
+            })
+            .map_err(|e| println!("> Storage unavailable, progress will not be saved: {}", e))
+            .ok();
+
+        Self { connection }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn open_at(_path: &str) -> Self {
+        Self {}
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let bytes = hex::decode(self.get_raw(key)?).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) {
+        match serde_json::to_vec(value) {
+            Ok(bytes) => self.set_raw(key, &hex::encode(bytes)),
+            Err(e) => println!("> Failed to encode '{}': {}", key, e),
+        }
+    }
+
+    /// Reads `key`, lets `change` edit it and writes it back inside one `BEGIN IMMEDIATE`
+    /// transaction, so no other connection can write the key in between. Returns `None` without
+    /// calling `change` when the database can't be locked. The transaction rolls back if
+    /// `change` panics.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn update<T: Serialize + DeserializeOwned + Default, R>(&self, key: &str, change: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let connection = self.connection.as_ref()?;
+
+        let transaction = match rusqlite::Transaction::new_unchecked(connection, rusqlite::TransactionBehavior::Immediate) {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                println!("> Failed to lock '{}': {}", key, e);
+                return None;
+            }
+        };
+
+        let mut value = self.get(key).unwrap_or_default();
+        let result = change(&mut value);
+        self.set(key, &value);
+
+        if let Err(e) = transaction.commit() {
+            println!("> Failed to save '{}': {}", key, e);
+            return None;
+        }
+
+        Some(result)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn update<T: Serialize + DeserializeOwned + Default, R>(&self, _key: &str, _change: impl FnOnce(&mut T) -> R) -> Option<R> {
+        None
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn remove(&self, key: &str) {
+        if let Some(connection) = &self.connection {
+            if let Err(e) = connection.execute("DELETE FROM kvs WHERE key = ?1", [key]) {
+                println!("> Failed to remove '{}': {}", key, e);
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn remove(&self, _key: &str) {}
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn get_raw(&self, key: &str) -> Option<String> {
+        self.connection.as_ref()?
+            .query_row("SELECT value FROM kvs WHERE key = ?1", [key], |row| row.get(0))
+            .ok()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn get_raw(&self, _key: &str) -> Option<String> {
+        None
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn set_raw(&self, key: &str, value: &str) {
+        if let Some(connection) = &self.connection {
+            let result = connection.execute(
+                "INSERT INTO kvs(key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                [key, value],
+            );
+
+            if let Err(e) = result {
+                println!("> Failed to save '{}': {}", key, e);
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn set_raw(&self, _key: &str, _value: &str) {}
+}
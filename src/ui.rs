@@ -0,0 +1,87 @@
+use prism::Context;
+use prism::canvas::{Image, ShapeType};
+use stork::{Canvas, GameObject};
+
+/// Everything a menu screen has put on the canvas, so it can take it all down again.
+#[derive(Debug)]
+pub struct Overlay {
+    tag: &'static str,
+    drawn: Vec<String>,
+}
+
+impl Overlay {
+    pub fn new(tag: &'static str) -> Self {
+        Self {
+            tag,
+            drawn: Vec::new(),
+        }
+    }
+
+    /// Puts `image` up as `name`, stretched to `size`.
+    pub fn add(
+        &mut self,
+        ctx: &mut Context,
+        canvas: &mut Canvas,
+        name: &str,
+        image: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
+        position: (f32, f32),
+        size: (f32, f32),
+    ) {
+        let img_obj = Image {
+            shape: ShapeType::Rectangle(0.0, size, 0.0),
+            image: image.into(),
+            color: None
+        };
+
+        let obj = GameObject::new_rect(
+            ctx,
+            name.to_string(),
+            img_obj,
+            size,
+            position,
+            vec![self.tag.to_string()],
+            (0.0, 0.0),
+            (1.0, 1.0),
+            0.0,
+        );
+
+        canvas.add_game_object(name.to_string(), obj);
+        self.drawn.push(name.to_string());
+    }
+
+    /// Keeps track of a number drawn with `ScoreManager::draw_number` under `prefix`.
+    pub fn track_digits(&mut self, prefix: &str) {
+        self.drawn.extend((0..10).map(|digit| format!("{}_{}", prefix, digit)));
+    }
+
+    pub fn clear(&mut self, canvas: &mut Canvas) {
+        for name in self.drawn.drain(..) {
+            canvas.remove_game_object(&name);
+        }
+    }
+}
+
+/// A white-rimmed disc `size` pixels across, painted inside the rim by `fill` from each pixel's
+/// offset from the centre.
+pub fn badge(size: u32, rim: f32, fill: impl Fn(f32, f32) -> image::Rgba<u8>) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+    let radius = size as f32 / 2.0;
+
+    image::ImageBuffer::from_fn(size, size, |x, y| {
+        let dx = x as f32 + 0.5 - radius;
+        let dy = y as f32 + 0.5 - radius;
+        let dist = (dx * dx + dy * dy).sqrt();
+
+        if dist > radius {
+            image::Rgba([0, 0, 0, 0])
+        } else if dist > radius - rim {
+            image::Rgba([255, 255, 255, 255])
+        } else {
+            fill(dx, dy)
+        }
+    })
+}
+
+/// A single pixel of `rgba`, for stretching into flat panels and bars.
+pub fn solid(rgba: [u8; 4]) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
+    image::ImageBuffer::from_pixel(1, 1, image::Rgba(rgba))
+}
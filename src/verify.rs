@@ -0,0 +1,120 @@
+use crate::daily;
+use crate::leaderboard::Submission;
+use crate::mode::ModeKind;
+use crate::replay::{Replay, REPLAY_VERSION};
+use crate::sim::Simulation;
+
+/// Fewest ticks between two flaps anyone can tap, about fifteen taps a second.
+pub const MIN_FLAP_TICKS: u64 = 4;
+/// Longest replay checked, an hour of play, so a bogus one can't tie the server up.
+pub const MAX_TICKS: u64 = 225_000;
+
+/// Accepts `submission` only if its replay is a run of its board's course that scores what it claims.
+pub fn verify(submission: &Submission) -> Result<(), String> {
+    let replay = &submission.replay;
+
+    if replay.version != REPLAY_VERSION {
+        return Err(format!("Replay version {} isn't supported", replay.version));
+    }
+
+    check_board(&submission.board, replay)?;
+    check_inputs(replay)?;
+
+    let score = replay_score(replay)?;
+    if score != submission.score {
+        return Err(format!("Replay scores {}, not {}", score, submission.score));
+    }
+
+    Ok(())
+}
+
+/// Plays `replay` headlessly and returns its score, or an error if the bird is down before it ends.
+pub fn replay_score(replay: &Replay) -> Result<usize, String> {
+    let mut sim = Simulation::new(replay.mode, replay.generator, replay.seed);
+    let mut flaps = replay.flaps.iter().peekable();
+
+    for tick in 0..replay.ticks {
+        let flapped = flaps.next_if_eq(&&tick).is_some();
+
+        if let Err(ending) = sim.step([flapped, false]) {
+            return Err(format!("Run ended ({:?}) at tick {} of {}", ending, tick, replay.ticks));
+        }
+    }
+
+    Ok(sim.score())
+}
+
+/// The board must be one the game submits to, and the replay must be on that board's course.
+fn check_board(board: &str, replay: &Replay) -> Result<(), String> {
+    let mode = replay.mode;
+    if replay.generator != mode.build().generator() {
+        return Err(format!("{:?} runs aren't played on {:?} courses", mode, replay.generator));
+    }
+
+    if let Some(date) = board.strip_prefix("daily-") {
+        let day = daily::parse_date(date).ok_or_else(|| format!("Unknown board {}", board))?;
+        if mode != ModeKind::Classic || replay.seed != daily::seed(day) {
+            return Err(format!("Replay isn't the {} daily course", date));
+        }
+        return Ok(());
+    }
+
+    let ranked = [ModeKind::Classic, ModeKind::TimeAttack, ModeKind::Zen, ModeKind::Hardcore];
+    if !ranked.contains(&mode) || board != mode.name() {
+        return Err(format!("A {:?} run can't go on board {}", mode, board));
+    }
+
+    Ok(())
+}
+
+/// Rejects flaps that are out of order, outside the run or closer together than a person can tap.
+fn check_inputs(replay: &Replay) -> Result<(), String> {
+    if replay.ticks > MAX_TICKS {
+        return Err(format!("Replay is {} ticks, longer than the {} allowed", replay.ticks, MAX_TICKS));
+    }
+
+    if let Some(last) = replay.flaps.last().filter(|last| **last >= replay.ticks) {
+        return Err(format!("Flap at tick {} is after the run ended at {}", last, replay.ticks));
+    }
+
+    for pair in replay.flaps.windows(2) {
+        if pair[1] <= pair[0] {
+            return Err(format!("Flaps at ticks {} and {} are out of order", pair[0], pair[1]));
+        }
+
+        if pair[1] - pair[0] < MIN_FLAP_TICKS {
+            return Err(format!("Flaps at ticks {} and {} are too close together", pair[0], pair[1]));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::Bot;
+    use crate::replay::ReplayRecorder;
+
+    #[test]
+    fn recorded_run_replays_to_its_score() {
+        let (mode, seed) = (ModeKind::Classic, 7);
+        let generator = mode.build().generator();
+        let mut sim = Simulation::new(mode, generator, seed);
+        let mut bot = Bot::new(0.8, seed);
+        let mut recorder = ReplayRecorder::new();
+
+        // Recorded the way `Game` does it: a tick counts once it has been played.
+        while sim.tick() < 20_000 {
+            let flapped = bot.decide(&sim);
+            if sim.step([flapped, false]).is_err() {
+                break;
+            }
+            recorder.record(flapped);
+        }
+        assert!(sim.score() > 0);
+
+        let replay = recorder.finish(mode, generator, seed).expect("Failed to finish replay");
+        assert_eq!(replay_score(&replay), Ok(sim.score()));
+    }
+}
@@ -0,0 +1,112 @@
+use prism::Context;
+use prism::canvas::{Image, ShapeType};
+use stork::{Canvas, GameObject};
+
+use crate::bird::Bird;
+use crate::score::{DigitStyle, ScoreManager};
+use crate::skin::BirdSkin;
+
+/// Canvas name of the second bird.
+pub const RIVAL_NAME: &str = "flappybird_2";
+/// Key player two flaps with; player one keeps "w".
+pub const RIVAL_KEY: &str = "p";
+const RIVAL_START: (f32, f32) = (200.0, 220.0);
+/// Where player one starts, and so where an online opponent's bird starts too.
+const PLAYER_START: (f32, f32) = (200.0, 300.0);
+
+const ICON_SIZE: f32 = 24.0;
+
+/// Player two's bird on screen; their score and whether they are still flying live in
+/// `Simulation::rival`.
+#[derive(Debug)]
+pub struct Versus {
+    pub rival: Bird,
+    /// Player two is an online opponent: flaps come over the network.
+    pub remote: bool,
+    /// Player two's bird, decoded once for the score icon.
+    icon: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
+    start: (f32, f32),
+}
+
+impl Versus {
+    pub fn new(rival: Bird, skin: BirdSkin) -> Self {
+        Self {
+            rival,
+            remote: false,
+            icon: skin.preview(),
+            start: RIVAL_START,
+        }
+    }
+
+    /// Height player two starts a round at.
+    pub fn start_y(&self) -> f32 {
+        self.start.1
+    }
+
+    pub fn spawn(&mut self, ctx: &mut Context, canvas: &mut Canvas) {
+        self.rival.spawn(ctx, canvas, self.start);
+    }
+
+    /// Hands player two to an online opponent, whose bird starts where theirs does on their screen.
+    pub fn set_remote(&mut self, canvas: &mut Canvas, remote: bool) {
+        self.remote = remote;
+        self.start = if remote { PLAYER_START } else { RIVAL_START };
+        self.hold(canvas);
+    }
+
+    pub fn despawn(&mut self, canvas: &mut Canvas) {
+        canvas.remove_game_object(RIVAL_NAME);
+        self.clear_display(canvas);
+    }
+
+    /// Keeps player two waiting at the start while a menu is open.
+    pub fn hold(&self, canvas: &mut Canvas) {
+        if let Some(bird) = canvas.get_game_object_mut(RIVAL_NAME) {
+            bird.position = self.start;
+            bird.momentum = (0.0, 0.0);
+        }
+    }
+
+    /// Player two's score under player one's, marked with player two's bird.
+    pub fn draw(&self, ctx: &mut Context, canvas: &mut Canvas, score_manager: &ScoreManager, rival_score: usize, right_x: f32) {
+        let width = score_manager.draw_number(ctx, canvas, "p2_score_digit", rival_score, (right_x, 104.0), DigitStyle::LARGE);
+        let position = (right_x - width - ICON_SIZE - 6.0, 111.0);
+
+        if let Some(icon) = canvas.get_game_object_mut("p2_icon") {
+            icon.position = position;
+            return;
+        }
+
+        let icon_obj = Image {
+            shape: ShapeType::Rectangle(0.0, (ICON_SIZE, ICON_SIZE), 0.0),
+            image: self.icon.clone().into(),
+            color: None
+        };
+
+        let icon = GameObject::new_rect(
+            ctx,
+            "p2_icon".to_string(),
+            icon_obj,
+            (ICON_SIZE, ICON_SIZE),
+            position,
+            vec!["score".to_string()],
+            (0.0, 0.0),
+            (1.0, 1.0),
+            0.0,
+        );
+
+        canvas.add_game_object("p2_icon".to_string(), icon);
+    }
+
+    fn clear_display(&self, canvas: &mut Canvas) {
+        for idx in 0..10 {
+            canvas.remove_game_object(&format!("p2_score_digit_{}", idx));
+        }
+        canvas.remove_game_object("p2_icon");
+    }
+
+    pub fn reset(&mut self, canvas: &mut Canvas) {
+        self.rival.reset();
+        self.hold(canvas);
+    }
+}
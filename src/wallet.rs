@@ -0,0 +1,34 @@
+use serde::{Serialize, Deserialize};
+
+use crate::storage::Storage;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Wallet {
+    pub coins: u64,
+}
+
+impl Wallet {
+    const KEY: &'static str = "FlappyWallet";
+
+    pub fn load(storage: &Storage) -> Self {
+        storage.get(Self::KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &Storage) {
+        storage.set(Self::KEY, self);
+    }
+
+    pub fn deposit(&mut self, coins: u64) {
+        self.coins = self.coins.saturating_add(coins);
+    }
+
+    /// Takes `cost` coins out of the wallet, or returns false and leaves it untouched.
+    pub fn spend(&mut self, cost: u64) -> bool {
+        if self.coins < cost {
+            return false;
+        }
+
+        self.coins -= cost;
+        true
+    }
+}
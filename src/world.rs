@@ -0,0 +1,32 @@
+#[derive(Debug, Clone, Copy)]
+pub struct WorldSpeed {
+    pub base: f32,
+    pub scale: f32,
+}
+
+impl WorldSpeed {
+    pub const DEFAULT: f32 = -3.0;
+
+    pub fn new(base: f32) -> Self {
+        Self {
+            base,
+            scale: 1.0,
+        }
+    }
+
+    /// Horizontal distance every scrolling element moves per tick.
+    pub fn value(&self) -> f32 {
+        self.base * self.scale
+    }
+
+    /// Current speed relative to the default, used to keep spawn timers in step with distance.
+    pub fn ratio(&self) -> f32 {
+        self.value() / Self::DEFAULT
+    }
+}
+
+impl Default for WorldSpeed {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT)
+    }
+}